@@ -0,0 +1,265 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+
+use crate::models::Diagnostic;
+
+// Offsets (in seconds east of UTC) for zone names publishers actually use
+const NAMED_ZONES: &[(&str, i32)] = &[
+    ("UT", 0), ("UTC", 0), ("GMT", 0), ("Z", 0), ("WET", 0),
+    ("EST", -5 * 3600), ("EDT", -4 * 3600),
+    ("CST", -6 * 3600), ("CDT", -5 * 3600),
+    ("MST", -7 * 3600), ("MDT", -6 * 3600),
+    ("PST", -8 * 3600), ("PDT", -7 * 3600),
+    ("AKST", -9 * 3600), ("AKDT", -8 * 3600),
+    ("HST", -10 * 3600),
+    ("AST", -4 * 3600), ("ADT", -3 * 3600),
+    ("NST", -(3 * 3600 + 1800)), ("NDT", -(2 * 3600 + 1800)),
+    ("BRT", -3 * 3600), ("ART", -3 * 3600),
+    ("BST", 3600), ("IST", 5 * 3600 + 1800), ("WEST", 3600),
+    ("CET", 3600), ("CEST", 2 * 3600), ("MET", 3600), ("MEST", 2 * 3600),
+    ("EET", 2 * 3600), ("EEST", 3 * 3600), ("MSK", 3 * 3600),
+    ("SGT", 8 * 3600), ("HKT", 8 * 3600), ("AWST", 8 * 3600),
+    ("JST", 9 * 3600), ("KST", 9 * 3600),
+    ("ACST", 9 * 3600 + 1800), ("ACDT", 10 * 3600 + 1800),
+    ("AEST", 10 * 3600), ("AEDT", 11 * 3600),
+    ("NZST", 12 * 3600), ("NZDT", 13 * 3600),
+];
+
+// English month names plus the localized spellings seen in feeds, matched on the first letters
+const MONTHS: &[(&str, u32)] = &[
+    ("jan", 1), ("ene", 1), ("gen", 1), ("janv", 1), ("jän", 1),
+    ("feb", 2), ("fév", 2), ("fev", 2), ("febr", 2),
+    ("mar", 3), ("mär", 3), ("maa", 3),
+    ("apr", 4), ("avr", 4), ("abr", 4),
+    ("may", 5), ("mai", 5), ("mei", 5), ("mag", 5), ("mayo", 5),
+    ("jun", 6), ("juin", 6), ("giu", 6),
+    ("jul", 7), ("juil", 7), ("lug", 7),
+    ("aug", 8), ("aoû", 8), ("aou", 8), ("ago", 8),
+    ("sep", 9), ("set", 9),
+    ("oct", 10), ("okt", 10), ("ott", 10), ("out", 10),
+    ("nov", 11),
+    ("dec", 12), ("déc", 12), ("dez", 12), ("dic", 12),
+];
+
+/*
+* Parse a feed date leniently, keeping the publisher's offset:
+* - unix timestamps, strict RFC 2822 and RFC 3339
+* - ISO 8601 without an offset, with a space separator or date only (taken as UTC)
+* - RFC 822 style dates with named zones (EDT, CEST), "GMT+2" style offsets,
+*   missing seconds, two-digit years, day/month in either order and
+*   localized day or month names
+*/
+pub fn parse(raw: &str) -> Option<DateTime<FixedOffset>> {
+    parse_with_zone(raw).map(|(dt, _)| dt)
+}
+
+// Publisher's UTC offset in minutes, None when the date didn't state one (timestamps, bare ISO dates)
+pub fn offset_minutes(raw: &str) -> Option<i32> {
+    parse_with_zone(raw)
+        .filter(|(_, has_zone)| *has_zone)
+        .map(|(dt, _)| dt.offset().local_minus_utc() / 60)
+}
+
+// The parsed date and whether the input carried its own zone (otherwise UTC was assumed)
+fn parse_with_zone(raw: &str) -> Option<(DateTime<FixedOffset>, bool)> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if let Ok(num) = s.parse::<i64>() {
+        return DateTime::from_timestamp(num, 0).map(|dt| (dt.fixed_offset(), false));
+    }
+
+    DateTime::parse_from_rfc2822(s)
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .ok()
+        .map(|dt| (dt, true))
+        .or_else(|| parse_iso_like(s))
+        .or_else(|| parse_rfc822_like(s))
+}
+
+// Unix timestamp of a feed date; integers are taken as timestamps already
+pub fn timestamp(raw: &str) -> Option<i64> {
+    let s = raw.trim();
+    if let Ok(num) = s.parse::<i64>() {
+        return Some(num);
+    }
+    parse(s).map(|dt| dt.timestamp())
+}
+
+// Timestamp for a date element, recording a diagnostic when the value can't be read
+pub fn timestamp_or_report(raw: &str, element: &str, diagnostics: &mut Vec<Diagnostic>) -> i64 {
+    match timestamp(raw) {
+        Some(timestamp) => timestamp,
+        None => {
+            if !raw.trim().is_empty() {
+                diagnostics.push(Diagnostic {
+                    code: "unparseable_date".to_string(),
+                    element: element.to_string(),
+                    value: raw.trim().to_string(),
+                });
+            }
+            0
+        }
+    }
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).unwrap()
+}
+
+fn parse_iso_like(s: &str) -> Option<(DateTime<FixedOffset>, bool)> {
+    for fmt in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%z", "%Y-%m-%dT%H:%M%z", "%Y-%m-%d %H:%M %z"] {
+        if let Ok(dt) = DateTime::parse_from_str(s, fmt) {
+            return Some((dt, true));
+        }
+    }
+
+    // a trailing Z is an explicit UTC designator, anything else without an offset is assumed UTC
+    let has_zone = s.ends_with('Z');
+    let s = s.trim_end_matches('Z');
+    for fmt in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return utc().from_local_datetime(&naive).single().map(|dt| (dt, has_zone));
+        }
+    }
+
+    for fmt in ["%Y-%m-%d", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(s, fmt) {
+            return utc()
+                .from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .single()
+                .map(|dt| (dt, has_zone));
+        }
+    }
+
+    None
+}
+
+fn parse_rfc822_like(s: &str) -> Option<(DateTime<FixedOffset>, bool)> {
+    let mut day: Option<u32> = None;
+    let mut month: Option<u32> = None;
+    let mut year: Option<i32> = None;
+    let mut time: Option<NaiveTime> = None;
+    let mut offset: Option<FixedOffset> = None;
+    let mut pm: Option<bool> = None;
+
+    // A leading "Day," is dropped before looking at tokens; "Mar," is Tuesday in Spanish, not March
+    let s = match s.split_once(',') {
+        Some((head, rest)) if !head.is_empty() && head.chars().all(|c| c.is_alphabetic() || c == '.') => rest,
+        _ => s,
+    };
+
+    let tokens = s.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty());
+    for (index, token) in tokens.enumerate() {
+        if time.is_none() && token.contains(':') && token.starts_with(|c: char| c.is_ascii_digit()) {
+            time = Some(parse_time(token)?);
+        } else if token.chars().all(|c| c.is_ascii_digit()) {
+            let value: i32 = token.parse().ok()?;
+            if token.len() == 4 && year.is_none() {
+                year = Some(value);
+            } else if day.is_none() {
+                day = Some(value as u32);
+            } else if year.is_none() {
+                year = Some(if value < 50 { 2000 + value } else { 1900 + value });
+            } else {
+                return None;
+            }
+        } else if let Some(m) = month_from_name(token).filter(|_| month.is_none()) {
+            month = Some(m);
+        } else if token.eq_ignore_ascii_case("am") || token.eq_ignore_ascii_case("pm") {
+            pm = Some(token.eq_ignore_ascii_case("pm"));
+        } else if let Some(o) = parse_zone(token).filter(|_| offset.is_none()) {
+            offset = Some(o);
+        } else if index == 0 && token.chars().all(|c| c.is_alphabetic() || c == '.') {
+            // day name in whatever language the publisher used
+            continue;
+        } else {
+            return None;
+        }
+    }
+
+    let mut time = time.unwrap_or(NaiveTime::MIN);
+    if let Some(pm) = pm {
+        time = time.with_hour(time.hour() % 12 + if pm { 12 } else { 0 })?;
+    }
+
+    let date = NaiveDate::from_ymd_opt(year?, month?, day?)?;
+    offset
+        .unwrap_or_else(utc)
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .map(|dt| (dt, offset.is_some()))
+}
+
+fn parse_time(token: &str) -> Option<NaiveTime> {
+    // "10:00:00+0200" style times carry their offset inline; that form is rare enough to skip
+    let parts: Vec<&str> = token.split(':').collect();
+    let hour: u32 = parts.first()?.parse().ok()?;
+    let minute: u32 = parts.get(1)?.parse().ok()?;
+    let (second, nanos) = match parts.get(2) {
+        Some(sec) => {
+            let mut split = sec.splitn(2, '.');
+            let whole: u32 = split.next()?.parse().ok()?;
+            let frac = split.next().unwrap_or("");
+            let nanos = if frac.is_empty() {
+                0
+            } else {
+                format!("{:0<9}", &frac[..frac.len().min(9)]).parse().ok()?
+            };
+            (whole, nanos)
+        }
+        None => (0, 0),
+    };
+    if parts.len() > 3 {
+        return None;
+    }
+    NaiveTime::from_hms_nano_opt(hour, minute, second.min(59), nanos)
+}
+
+fn month_from_name(token: &str) -> Option<u32> {
+    let lower = token.trim_end_matches('.').to_lowercase();
+    if lower.chars().count() < 3 || !lower.chars().all(char::is_alphabetic) {
+        return None;
+    }
+    // longest matching prefix wins so "juil" beats "jul" and "mayo" beats "may"
+    MONTHS
+        .iter()
+        .filter(|(name, _)| lower.starts_with(name))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, m)| *m)
+}
+
+// "+0200", "-05:00", "EDT", "GMT+2", "UTC+05:30"
+fn parse_zone(token: &str) -> Option<FixedOffset> {
+    let upper = token.trim_matches(|c| c == '(' || c == ')').to_ascii_uppercase();
+
+    if let Some((_, secs)) = NAMED_ZONES.iter().find(|(name, _)| *name == upper) {
+        return FixedOffset::east_opt(*secs);
+    }
+
+    let numeric = ["GMT", "UTC", "UT"]
+        .iter()
+        .find_map(|prefix| upper.strip_prefix(prefix))
+        .unwrap_or(&upper);
+
+    let (sign, digits) = match numeric.chars().next()? {
+        '+' => (1, &numeric[1..]),
+        '-' => (-1, &numeric[1..]),
+        _ => return None,
+    };
+    let digits = digits.replace(':', "");
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes): (i32, i32) = match digits.len() {
+        1 | 2 => (digits.parse().ok()?, 0),
+        3 => (digits[..1].parse().ok()?, digits[1..].parse().ok()?),
+        4 => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
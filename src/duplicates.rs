@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use crate::mime::{self, MediaKind};
+use crate::models::Diagnostic;
+use crate::outputs::SqlInsert;
+use crate::parser_state::ParserState;
+
+// An nfitems record held until the channel closes, with what's needed to spot its duplicates
+pub struct PendingItem {
+    pub record: SqlInsert,
+    pub guid: String,
+    pub media_kind: MediaKind,
+    pub pub_date: i64,
+    // a guid the feed declared, rather than the enclosure URL standing in for one
+    explicit_guid: bool,
+    enclosure_url: String,
+    title: String,
+    // how many optional fields the item filled in; the fullest copy is kept
+    completeness: usize,
+}
+
+impl PendingItem {
+    // Built in item::on_end, once the item's fields are final
+    pub fn new(state: &ParserState, record: SqlInsert) -> PendingItem {
+        let explicit_guid = !state.guid.trim().is_empty() && state.guid != state.enclosure_url;
+        let declared_duration = !matches!(state.itunes_duration_source.as_str(), "estimated" | "missing");
+        let has_description = !state.description.trim().is_empty()
+            || !state.content_encoded.trim().is_empty()
+            || !state.itunes_summary.trim().is_empty();
+        let has_image = !state.itunes_image.is_empty() || !state.item_image.is_empty();
+        let has_length = state.enclosure_length.trim().parse::<u64>().is_ok_and(|length| length > 0);
+
+        let text = |column: &str| record.value(column).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        PendingItem {
+            guid: text("guid"),
+            media_kind: mime::media_kind(&state.enclosure_type),
+            pub_date: state.pub_date,
+            explicit_guid,
+            enclosure_url: state.enclosure_url.trim().to_string(),
+            title: state.title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+            completeness: [
+                explicit_guid,
+                declared_duration,
+                has_description,
+                has_image,
+                has_length,
+                !state.title.trim().is_empty(),
+                state.pub_date != 0,
+            ]
+            .iter()
+            .filter(|filled| **filled)
+            .count(),
+            record,
+        }
+    }
+}
+
+/*
+* The items of a feed with duplicates folded together. Two items are the same episode
+* when they share a guid; an item without a guid of its own is also matched on its
+* enclosure URL, else its title and pub date. The most complete copy is kept (the first
+* one on a tie) in the position of the first copy.
+*/
+#[derive(Default)]
+pub struct ItemSet {
+    kept: Vec<PendingItem>,
+    by_guid: HashMap<String, usize>,
+    by_enclosure_url: HashMap<String, usize>,
+    by_title_pub_date: HashMap<(String, i64), usize>,
+    // the key that matched and the copy that lost
+    dropped: Vec<(&'static str, PendingItem)>,
+}
+
+impl ItemSet {
+    pub fn add(&mut self, item: PendingItem) {
+        let title_pub_date = (!item.title.is_empty() && item.pub_date != 0)
+            .then(|| (item.title.clone(), item.pub_date));
+
+        // items whose declared guids differ are never the same episode
+        let existing = self
+            .by_guid
+            .get(&item.guid)
+            .map(|index| ("guid", *index))
+            .or_else(|| {
+                self.by_enclosure_url
+                    .get(&item.enclosure_url)
+                    .filter(|_| !item.explicit_guid)
+                    .map(|index| ("enclosure_url", *index))
+            })
+            .or_else(|| {
+                title_pub_date
+                    .as_ref()
+                    .filter(|_| !item.explicit_guid)
+                    .and_then(|key| self.by_title_pub_date.get(key))
+                    .map(|index| ("title_pub_date", *index))
+            });
+
+        let index = match existing {
+            Some((_, index)) => index,
+            None => self.kept.len(),
+        };
+        if !item.guid.is_empty() {
+            self.by_guid.entry(item.guid.clone()).or_insert(index);
+        }
+        if !item.enclosure_url.is_empty() {
+            self.by_enclosure_url.entry(item.enclosure_url.clone()).or_insert(index);
+        }
+        if let Some(key) = title_pub_date {
+            self.by_title_pub_date.entry(key).or_insert(index);
+        }
+
+        match existing {
+            None => self.kept.push(item),
+            Some((matched_on, index)) if item.completeness > self.kept[index].completeness => {
+                let replaced = std::mem::replace(&mut self.kept[index], item);
+                self.dropped.push((matched_on, replaced));
+            }
+            Some((matched_on, _)) => self.dropped.push((matched_on, item)),
+        }
+    }
+}
+
+// Take the deduplicated items, listing each dropped copy in the diagnostics
+pub fn resolve(state: &mut ParserState) -> Vec<PendingItem> {
+    let items = std::mem::take(&mut state.pending_items);
+    state.duplicate_items = items.dropped.len();
+    for (matched_on, item) in &items.dropped {
+        state.diagnostics.push(Diagnostic {
+            code: "duplicate_item".to_string(),
+            element: matched_on.to_string(),
+            value: item.guid.clone(),
+        });
+    }
+    items.kept
+}
@@ -0,0 +1,147 @@
+// Bitrate assumed when estimating an audio duration from the enclosure size (128 kbps)
+pub const ESTIMATE_BITS_PER_SECOND: i64 = 128_000;
+
+// Enclosure lengths below this are placeholders ("0", "1") rather than real file sizes
+const MIN_ESTIMATE_BYTES: i64 = 100_000;
+
+/*
+* Parse a duration into whole seconds. Accepts:
+* - plain seconds, fractional or not: "3600", "62.5"
+* - clock values: "1:02:03.500", "62:03", "02:03"
+* - units: "45 min", "1h 20m", "1 hr 20 mins 5 sec", "90s"
+* - ISO 8601: "PT1H2M3S", "PT45M", "P1DT2H"
+* Returns None for anything else instead of guessing.
+*/
+pub fn parse(raw: &str) -> Option<i64> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if s.contains(':') {
+        return parse_clock(s);
+    }
+
+    if let Some(rest) = s.strip_prefix(['P', 'p']) {
+        return parse_iso8601(rest);
+    }
+
+    if let Ok(seconds) = s.parse::<f64>() {
+        return whole_seconds(seconds);
+    }
+
+    parse_units(s)
+}
+
+// Rough duration for an audio enclosure from its byte length, at ESTIMATE_BITS_PER_SECOND
+pub fn estimate(enclosure_length: &str, enclosure_type: &str) -> Option<i64> {
+    if !enclosure_type.trim().to_ascii_lowercase().starts_with("audio/") {
+        return None;
+    }
+
+    let bytes = enclosure_length.trim().parse::<i64>().ok()?;
+    if bytes < MIN_ESTIMATE_BYTES {
+        return None;
+    }
+
+    Some(bytes.saturating_mul(8) / ESTIMATE_BITS_PER_SECOND)
+}
+
+fn whole_seconds(seconds: f64) -> Option<i64> {
+    if !seconds.is_finite() || seconds < 0.0 || seconds >= i64::MAX as f64 {
+        return None;
+    }
+    Some(seconds.round() as i64)
+}
+
+fn parse_clock(s: &str) -> Option<i64> {
+    let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+    if parts.len() > 3 || parts.iter().any(|p| p.is_empty()) {
+        return None;
+    }
+
+    // only the last (seconds) part may be fractional
+    let (last, leading) = parts.split_last()?;
+    let mut total = 0.0;
+    for part in leading {
+        if !part.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        total = total * 60.0 + part.parse::<f64>().ok()?;
+    }
+    if !last.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    whole_seconds(total * 60.0 + last.parse::<f64>().ok()?)
+}
+
+fn parse_iso8601(s: &str) -> Option<i64> {
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut components = 0;
+
+    for c in s.chars() {
+        match c.to_ascii_uppercase() {
+            'T' if number.is_empty() && !in_time => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            unit => {
+                let value = number.parse::<f64>().ok()?;
+                number.clear();
+                total += value * match (unit, in_time) {
+                    ('W', false) => 7.0 * 86400.0,
+                    ('D', false) => 86400.0,
+                    ('H', true) => 3600.0,
+                    ('M', true) => 60.0,
+                    ('S', true) => 1.0,
+                    // years and months have no fixed length
+                    _ => return None,
+                };
+                components += 1;
+            }
+        }
+    }
+
+    if !number.is_empty() || components == 0 {
+        return None;
+    }
+    whole_seconds(total)
+}
+
+fn parse_units(s: &str) -> Option<i64> {
+    let lower = s.to_ascii_lowercase();
+    let mut total = 0.0;
+    let mut components = 0;
+    let mut chars = lower.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut number = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic() || *c == '.') {
+            unit.push(c);
+        }
+
+        let value = number.parse::<f64>().ok()?;
+        total += value * match unit.trim_end_matches('.') {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            _ => return None,
+        };
+        components += 1;
+    }
+
+    if components == 0 {
+        return None;
+    }
+    whole_seconds(total)
+}
@@ -0,0 +1,117 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+
+// How much of the payload is searched for the <?xml ...?> declaration
+const DECLARATION_SCAN_BYTES: usize = 1024;
+
+// The encoding a payload was read with, for the newsfeeds audit columns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodingInfo {
+    // encoding_rs name of the encoding actually used ("UTF-8", "windows-1252", ...)
+    pub detected: String,
+    // the label from the XML declaration as written, empty when there was none
+    pub declared: String,
+}
+
+/*
+* Convert an XML payload to UTF-8 before it reaches the parser. The encoding is taken from:
+* 1. a byte order mark
+* 2. the XML declaration, unless the bytes contradict it (a single-byte label on valid,
+*    non-ASCII UTF-8 is the usual mislabel)
+* 3. a statistical guess when the payload isn't valid UTF-8
+* The declaration is rewritten to say UTF-8 so the parser doesn't decode a second time.
+*/
+pub fn transcode_to_utf8(bytes: &[u8]) -> (Vec<u8>, EncodingInfo) {
+    let declared = declared_label(bytes);
+    let declared_encoding = Encoding::for_label(declared.as_bytes());
+
+    let (encoding, body) = if let Some((bom_encoding, bom_len)) = Encoding::for_bom(bytes) {
+        (bom_encoding, &bytes[bom_len..])
+    } else {
+        let is_utf8 = std::str::from_utf8(bytes).is_ok();
+        let encoding = match declared_encoding {
+            // labelled single-byte but really UTF-8
+            Some(enc) if enc.is_single_byte() && is_utf8 && !bytes.is_ascii() => UTF_8,
+            // labelled UTF-8 but really something else
+            Some(enc) if enc == UTF_8 && !is_utf8 => guess(bytes),
+            // a UTF-16 label on a BOM-less payload whose declaration we could read as ASCII
+            Some(enc) if enc != UTF_8 && enc.output_encoding() == UTF_8 => guess_unless_utf8(bytes, is_utf8),
+            Some(enc) => enc,
+            None => guess_unless_utf8(bytes, is_utf8),
+        };
+        (encoding, bytes)
+    };
+
+    let info = EncodingInfo {
+        detected: encoding.name().to_string(),
+        declared,
+    };
+
+    if encoding == UTF_8 && (info.declared.is_empty() || declared_encoding == Some(UTF_8)) {
+        return (body.to_vec(), info);
+    }
+
+    let (text, _) = encoding.decode_without_bom_handling(body);
+    (rewrite_declaration(&text).into_bytes(), info)
+}
+
+fn guess_unless_utf8(bytes: &[u8], is_utf8: bool) -> &'static Encoding {
+    if is_utf8 { UTF_8 } else { guess(bytes) }
+}
+
+fn guess(bytes: &[u8]) -> &'static Encoding {
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+// The encoding="..." value of a leading XML declaration
+fn declared_label(bytes: &[u8]) -> String {
+    let head = &bytes[..bytes.len().min(DECLARATION_SCAN_BYTES)];
+    let head = String::from_utf8_lossy(head);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+
+    if !head.starts_with("<?xml") {
+        return String::new();
+    }
+    let declaration = match head.find("?>") {
+        Some(end) => &head[..end],
+        None => return String::new(),
+    };
+
+    attribute_value(declaration, "encoding")
+        .map(|(_, value)| value.trim().to_string())
+        .unwrap_or_default()
+}
+
+// Byte range (start of the value, the value) of `name="value"` or `name='value'` in a declaration
+fn attribute_value<'a>(declaration: &'a str, name: &str) -> Option<(usize, &'a str)> {
+    let pos = declaration.find(name)?;
+    let rest = &declaration[pos + name.len()..];
+    let eq = rest.find('=')?;
+    let after_eq = rest[eq + 1..].trim_start();
+    let quote = after_eq.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value_start = declaration.len() - after_eq.len() + 1;
+    let value_len = after_eq[1..].find(quote)?;
+    Some((value_start, &declaration[value_start..value_start + value_len]))
+}
+
+fn rewrite_declaration(text: &str) -> String {
+    let trimmed = text.trim_start();
+    let offset = text.len() - trimmed.len();
+    if !trimmed.starts_with("<?xml") {
+        return text.to_string();
+    }
+    let end = match trimmed.find("?>") {
+        Some(end) => end,
+        None => return text.to_string(),
+    };
+
+    match attribute_value(&trimmed[..end], "encoding") {
+        Some((start, value)) => {
+            let start = offset + start;
+            format!("{}UTF-8{}", &text[..start], &text[start + value.len()..])
+        }
+        None => text.to_string(),
+    }
+}
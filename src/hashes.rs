@@ -0,0 +1,67 @@
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+// nfitems columns that describe the parse rather than the item
+const ITEM_EXCLUDED: &[&str] = &["feed_id", "itunes_duration_source"];
+
+// newsfeeds columns that are derived from the items or describe the fetch rather than the feed.
+// The channel pub date is left out too: publishers bump it (or it falls back to
+// lastBuildDate) on every rebuild.
+const CHANNEL_EXCLUDED: &[&str] = &[
+    "feed_id",
+    "pub_date",
+    "pub_date_offset",
+    "pub_date_raw",
+    "last_build_date",
+    "newest_item_pub_date",
+    "oldest_item_pub_date",
+    "item_count",
+    "update_frequency",
+    "dominant_media_kind",
+    "encoding_detected",
+    "encoding_declared",
+    "parse_status",
+    "parse_error",
+    "limits_hit",
+    "items_added",
+    "items_changed",
+    "items_removed",
+    "duplicate_items",
+    "skipped_no_enclosure",
+    "skipped_empty_url",
+    "skipped_non_http_enclosure",
+    "skipped_invalid_url",
+];
+
+// Hash of an nfitems record, leaving out the parse metadata
+pub fn item_hash(columns: &[String], values: &[JsonValue]) -> String {
+    content_hash(columns, values, |column| !ITEM_EXCLUDED.contains(&column))
+}
+
+// Hash of a newsfeeds record, leaving out the columns that change on every fetch
+pub fn channel_hash(columns: &[String], values: &[JsonValue]) -> String {
+    content_hash(columns, values, |column| !CHANNEL_EXCLUDED.contains(&column))
+}
+
+// Hex SHA-256 over "column=value" lines, in column order
+fn content_hash(columns: &[String], values: &[JsonValue], include: impl Fn(&str) -> bool) -> String {
+    let mut hasher = Sha256::new();
+    for (column, value) in columns.iter().zip(values) {
+        if !include(column) {
+            continue;
+        }
+        hasher.update(column.as_bytes());
+        hasher.update(b"=");
+        hasher.update(normalize(value).as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+// Differences in whitespace aren't changes in content
+fn normalize(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.split_whitespace().collect::<Vec<_>>().join(" "),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use ammonia::Builder;
+
+static CLEANER: OnceLock<Builder<'static>> = OnceLock::new();
+
+// Markup kept in description_html; everything else is unwrapped to its text
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "blockquote", "br", "code", "em", "h1", "h2", "h3", "h4", "h5", "h6",
+    "i", "li", "ol", "p", "pre", "strong", "u", "ul",
+];
+
+// Tags whose boundaries separate words when flattened to plain text
+const BLOCK_TAGS: &[&str] = &[
+    "blockquote", "br", "h1", "h2", "h3", "h4", "h5", "h6", "li", "ol", "p", "pre", "ul",
+];
+
+/*
+* Reduce publisher HTML to a small whitelist for display:
+* - script/style blocks are removed along with their contents
+* - images (and with them tracking pixels), iframes and forms are dropped
+* - inline styles, classes and event handlers are stripped from every tag
+* - links keep only http(s)/mailto hrefs and get rel="noopener noreferrer nofollow"
+*/
+pub fn sanitize(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return String::new();
+    }
+
+    cleaner().clean(raw).to_string().trim().to_string()
+}
+
+// Built once; the whitelist is the same for every description
+fn cleaner() -> &'static Builder<'static> {
+    CLEANER.get_or_init(|| {
+        let mut builder = Builder::default();
+        builder
+            .tags(ALLOWED_TAGS.iter().copied().collect::<HashSet<_>>())
+            .generic_attributes(HashSet::new())
+            .tag_attributes(
+                [("a", ["href"].into_iter().collect::<HashSet<_>>())]
+                    .into_iter()
+                    .collect(),
+            )
+            .url_schemes(["http", "https", "mailto"].into_iter().collect())
+            .link_rel(Some("noopener noreferrer nofollow"));
+        builder
+    })
+}
+
+// Plain text for search from sanitize's output: tags removed, entities decoded, whitespace collapsed
+pub fn to_text(sanitized: &str) -> String {
+    let mut text = String::with_capacity(sanitized.len());
+
+    let mut chars = sanitized.chars();
+    while let Some(c) = chars.next() {
+        if c != '<' {
+            text.push(c);
+            continue;
+        }
+
+        // Sanitized markup is well formed, so a tag runs to the next '>' outside quotes
+        let mut tag = String::new();
+        let mut quote: Option<char> = None;
+        for t in chars.by_ref() {
+            match (t, quote) {
+                ('>', None) => break,
+                ('"' | '\'', None) => quote = Some(t),
+                (q, Some(open)) if q == open => quote = None,
+                _ => {}
+            }
+            tag.push(t);
+        }
+
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if BLOCK_TAGS.contains(&name.as_str()) {
+            text.push(' ');
+        }
+    }
+
+    html_escape::decode_html_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use crate::state_store::FeedIndex;
+
+/*
+* Diff of a feed's items against the last run, for incremental mode. Items are keyed by
+* guid and compared by content hash; only new and changed ones are written, and items
+* that are gone get a tombstone.
+*/
+#[derive(Debug, Default)]
+pub struct Incremental {
+    previous: BTreeMap<String, String>,
+    current: BTreeMap<String, String>,
+    pub added: usize,
+    pub changed: usize,
+    // guid and last content hash of items no longer in the feed, filled in by finish
+    pub removed: Vec<(String, String)>,
+}
+
+impl Incremental {
+    pub fn new(previous: FeedIndex) -> Incremental {
+        Incremental {
+            previous: previous.items,
+            ..Default::default()
+        }
+    }
+
+    // Remember an item; true when it's new or changed since the last run
+    pub fn track(&mut self, guid: &str, content_hash: &str) -> bool {
+        let emit = match self.previous.get(guid) {
+            None => {
+                self.added += 1;
+                true
+            }
+            Some(previous_hash) if previous_hash != content_hash => {
+                self.changed += 1;
+                true
+            }
+            Some(_) => false,
+        };
+        self.current.insert(guid.to_string(), content_hash.to_string());
+        emit
+    }
+
+    // Work out which items disappeared; only meaningful once the whole feed has been seen
+    pub fn finish(&mut self) {
+        self.removed = self
+            .previous
+            .iter()
+            .filter(|(guid, _)| !self.current.contains_key(*guid))
+            .map(|(guid, hash)| (guid.clone(), hash.clone()))
+            .collect();
+    }
+
+    // State to keep for the next run. A partial parse didn't see every item, so the
+    // earlier ones are kept rather than forgotten.
+    pub fn index(&self, complete: bool) -> FeedIndex {
+        let mut items = if complete { BTreeMap::new() } else { self.previous.clone() };
+        items.extend(self.current.iter().map(|(guid, hash)| (guid.clone(), hash.clone())));
+        FeedIndex {
+            items,
+            ..Default::default()
+        }
+    }
+}
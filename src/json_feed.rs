@@ -0,0 +1,217 @@
+use std::time::Instant;
+
+use serde_json::Value as JsonValue;
+
+use crate::dates;
+use crate::limits::{self, Limit, ParseLimits};
+use crate::models::ExplicitStatus;
+use crate::parser_state::ParserState;
+use crate::tags::{channel, item};
+use crate::urls::{self, UrlKind};
+use crate::utils;
+use crate::ParseStop;
+
+// Returns true when the payload looks like JSON rather than XML
+pub fn is_json_payload(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    bytes
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .map(|b| *b == b'{')
+        .unwrap_or(false)
+}
+
+/*
+* Map a JSON Feed (https://jsonfeed.org/version/1.1) document onto the same
+* channel/item lifecycle the XML handlers use, so newsfeeds and nfitems records
+* get identical fallbacks, validation and truncation. The XML parser's limits apply
+* too: the item cap stops the feed as partial, and text and keys are cut like XML text
+* and attributes.
+*/
+pub fn process(
+    bytes: &[u8],
+    limits: &ParseLimits,
+    deadline: Instant,
+    feed_id: Option<i64>,
+    state: &mut ParserState,
+) -> Result<(), ParseStop> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut feed: JsonValue = serde_json::from_slice(bytes).map_err(|e| ParseStop::Error(e.to_string()))?;
+
+    let version = str_field(&feed, "version");
+    if !version.contains("jsonfeed.org/version/") {
+        return Err(ParseStop::Error(format!("missing or unknown version '{}'", version)));
+    }
+    bound_values(&mut feed, "", limits, state);
+
+    channel::on_start(state);
+    state.feed_type = 3; // json feed
+
+    state.channel_title = str_field(&feed, "title");
+    state.channel_link = url_field(&feed, "home_page_url", state);
+    state.channel_pubsub_self_url = url_field(&feed, "feed_url", state);
+    state.channel_description = str_field(&feed, "description");
+    state.channel_image = url_field(&feed, "icon", state);
+    if state.channel_image.is_empty() {
+        state.channel_image = url_field(&feed, "favicon", state);
+    }
+    state.channel_itunes_author = first_author_name(&feed);
+    state.channel_language = str_field(&feed, "language");
+
+    if let Some(hubs) = feed.get("hubs").and_then(|h| h.as_array()) {
+        if let Some(hub) = hubs.iter().find(|h| str_field(h, "type").eq_ignore_ascii_case("websub")) {
+            state.channel_pubsub_hub_url = url_field(hub, "url", state);
+        }
+    }
+
+    if let Some(ext) = feed.get("_podcast") {
+        state.channel_podcast_guid = str_field(ext, "guid");
+        state.channel_explicit = explicit_status(ext);
+        if let Some(funding) = ext.get("funding") {
+            state.channel_podcast_funding_url = url_field(funding, "url", state);
+            state.channel_podcast_funding_text = str_field(funding, "message");
+        }
+    }
+
+    if let Some(items) = feed.get("items").and_then(|i| i.as_array()) {
+        for (index, entry) in items.iter().enumerate() {
+            if Instant::now() > deadline {
+                return Err(ParseStop::Timeout);
+            }
+            if index >= limits.max_items {
+                limits::record_at(state, Limit::ItemCount, "items");
+                return Err(ParseStop::Limit(Limit::ItemCount));
+            }
+            process_item(entry, feed_id, state);
+        }
+    }
+
+    channel::on_end(feed_id, state);
+    Ok(())
+}
+
+fn process_item(entry: &JsonValue, feed_id: Option<i64>, state: &mut ParserState) {
+    item::on_start(state);
+
+    state.guid = id_field(entry);
+    state.link = url_field(entry, "url", state);
+    if state.link.is_empty() {
+        state.link = url_field(entry, "external_url", state);
+    }
+    state.title = str_field(entry, "title");
+    state.content_encoded = str_field(entry, "content_html");
+    state.description = str_field(entry, "content_text");
+    state.itunes_summary = str_field(entry, "summary");
+    state.item_image = url_field(entry, "image", state);
+    if state.item_image.is_empty() {
+        state.item_image = url_field(entry, "banner_image", state);
+    }
+    state.item_itunes_author = first_author_name(entry);
+    let date_published = str_field(entry, "date_published");
+    state.pub_date_raw = date_published.trim().to_string();
+    state.pub_date = dates::timestamp_or_report(&date_published, "date_published", &mut state.diagnostics);
+
+    if let Some(ext) = entry.get("_podcast") {
+        state.itunes_explicit = explicit_status(ext);
+    }
+
+    // Only the first attachment with a usable URL becomes the enclosure
+    if let Some(attachments) = entry.get("attachments").and_then(|a| a.as_array()) {
+        state.item_enclosure_declared = !attachments.is_empty();
+        for attachment in attachments {
+            let url = url_field(attachment, "url", state);
+            // the first URL is kept when none is usable, to say why the item was skipped
+            if state.enclosure_url.is_empty() {
+                state.enclosure_url = url.clone();
+            }
+            if urls::is_valid(&url, UrlKind::Media, "attachments", &mut state.diagnostics) {
+                state.enclosure_url = url;
+                state.enclosure_type = str_field(attachment, "mime_type");
+                state.enclosure_length = attachment
+                    .get("size_in_bytes")
+                    .and_then(|v| v.as_i64())
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                if let Some(seconds) = attachment.get("duration_in_seconds").and_then(|v| v.as_f64()) {
+                    state.itunes_duration = seconds.round() as i32;
+                    state.itunes_duration_source = "declared".to_string();
+                }
+                state.item_has_valid_enclosure = true;
+                break;
+            }
+        }
+    }
+
+    item::on_end(feed_id, state);
+}
+
+// Cut strings to max_text_length and objects to max_attributes keys, as the XML parser
+// does with text and attributes; `key` is the field the value sits under
+fn bound_values(value: &mut JsonValue, key: &str, limits: &ParseLimits, state: &mut ParserState) {
+    match value {
+        JsonValue::String(text) if text.len() > limits.max_text_length => {
+            limits::record_at(state, Limit::TextLength, key);
+            let length = limits::truncate_bytes(text, limits.max_text_length).len();
+            text.truncate(length);
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                bound_values(value, key, limits, state);
+            }
+        }
+        JsonValue::Object(fields) => {
+            if fields.len() > limits.max_attributes {
+                limits::record_at(state, Limit::AttributeCount, key);
+                let extra: Vec<String> = fields.keys().skip(limits.max_attributes).cloned().collect();
+                for name in extra {
+                    fields.remove(&name);
+                }
+            }
+            for (name, value) in fields.iter_mut() {
+                bound_values(value, name, limits, state);
+            }
+        }
+        _ => {}
+    }
+}
+
+// A URL field resolved against the header feed URL; JSON Feed has no xml:base
+fn url_field(value: &JsonValue, key: &str, state: &ParserState) -> String {
+    utils::resolve_url(&state.feed_url, str_field(value, key).trim())
+}
+
+// Item ids should be strings, but numbers are common and still identify the item
+fn id_field(value: &JsonValue) -> String {
+    match value.get("id") {
+        Some(JsonValue::Number(n)) => n.to_string(),
+        _ => str_field(value, "id"),
+    }
+}
+
+fn str_field(value: &JsonValue, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+// JSON Feed 1.1 uses an `authors` array, 1.0 used a single `author` object
+fn first_author_name(value: &JsonValue) -> String {
+    value
+        .get("authors")
+        .and_then(|a| a.as_array())
+        .and_then(|a| a.first())
+        .or_else(|| value.get("author"))
+        .map(|a| str_field(a, "name"))
+        .unwrap_or_default()
+}
+
+fn explicit_status(ext: &JsonValue) -> ExplicitStatus {
+    match ext.get("explicit") {
+        Some(JsonValue::Bool(true)) => ExplicitStatus::Explicit,
+        Some(JsonValue::Bool(false)) => ExplicitStatus::Clean,
+        Some(JsonValue::String(s)) => ExplicitStatus::from_value(s),
+        _ => ExplicitStatus::Unspecified,
+    }
+}
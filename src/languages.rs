@@ -0,0 +1,110 @@
+use crate::utils;
+
+// Widest tag the newsfeeds language column holds
+pub const MAX_LANGUAGE_LENGTH: usize = 8;
+
+// ISO 639-2 (bibliographic and terminology) codes that have a two-letter equivalent
+const ISO_639_2: &[(&str, &str)] = &[
+    ("ara", "ar"), ("cat", "ca"), ("ces", "cs"), ("chi", "zh"), ("cze", "cs"),
+    ("dan", "da"), ("deu", "de"), ("dut", "nl"), ("ell", "el"), ("eng", "en"),
+    ("fin", "fi"), ("fra", "fr"), ("fre", "fr"), ("ger", "de"), ("gre", "el"),
+    ("heb", "he"), ("hin", "hi"), ("hun", "hu"), ("ind", "id"), ("ita", "it"),
+    ("jpn", "ja"), ("kor", "ko"), ("nld", "nl"), ("nor", "no"), ("pol", "pl"),
+    ("por", "pt"), ("ron", "ro"), ("rum", "ro"), ("rus", "ru"), ("spa", "es"),
+    ("swe", "sv"), ("tha", "th"), ("tur", "tr"), ("ukr", "uk"), ("vie", "vi"),
+    ("zho", "zh"),
+];
+
+// Language names publishers write instead of codes, in English and in the language itself
+const NAMES: &[(&str, &str)] = &[
+    ("arabic", "ar"), ("catalan", "ca"), ("català", "ca"), ("chinese", "zh"),
+    ("czech", "cs"), ("danish", "da"), ("dansk", "da"), ("deutsch", "de"),
+    ("dutch", "nl"), ("english", "en"), ("español", "es"), ("espanol", "es"),
+    ("finnish", "fi"), ("français", "fr"), ("francais", "fr"), ("french", "fr"),
+    ("german", "de"), ("greek", "el"), ("hebrew", "he"), ("hindi", "hi"),
+    ("hungarian", "hu"), ("indonesian", "id"), ("italian", "it"), ("italiano", "it"),
+    ("japanese", "ja"), ("korean", "ko"), ("nederlands", "nl"), ("norsk", "no"),
+    ("norwegian", "no"), ("polish", "pl"), ("polski", "pl"), ("portuguese", "pt"),
+    ("português", "pt"), ("portugues", "pt"), ("romanian", "ro"), ("russian", "ru"),
+    ("spanish", "es"), ("suomi", "fi"), ("svenska", "sv"), ("swedish", "sv"),
+    ("thai", "th"), ("turkish", "tr"), ("türkçe", "tr"), ("ukrainian", "uk"),
+    ("vietnamese", "vi"),
+];
+
+// Deprecated primary subtags still common in old feeds
+const LEGACY: &[(&str, &str)] = &[("iw", "he"), ("in", "id"), ("ji", "yi")];
+
+/*
+* Normalize a language value to a canonical BCP 47 tag:
+* - only the first entry of a list ("fr-FR,en") is kept
+* - "_" separators become "-"
+* - primary subtags are lowercased, scripts titlecased and regions uppercased ("EN_gb" -> "en-GB")
+* - ISO 639-2 codes and language names map to their two-letter code ("eng", "English" -> "en")
+* Returns None when the value doesn't look like a language at all.
+*/
+pub fn normalize(raw: &str) -> Option<String> {
+    let first = raw
+        .split([',', ';'])
+        .map(str::trim)
+        .find(|s| !s.is_empty())?;
+
+    let lower = first.to_lowercase();
+    if let Some((_, code)) = NAMES.iter().find(|(name, _)| *name == lower) {
+        return Some(code.to_string());
+    }
+
+    let mut subtags = first.split(['-', '_']);
+    let primary = subtags.next()?.to_ascii_lowercase();
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let primary = ISO_639_2
+        .iter()
+        .chain(LEGACY)
+        .find(|(code, _)| *code == primary)
+        .map(|(_, short)| short.to_string())
+        .unwrap_or(primary);
+
+    let mut tag = primary;
+    for subtag in subtags {
+        if subtag.is_empty() || subtag.len() > 8 || !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        tag.push('-');
+        match subtag.len() {
+            4 if subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                tag.push_str(&subtag[..1].to_ascii_uppercase());
+                tag.push_str(&subtag[1..].to_ascii_lowercase());
+            }
+            2 if subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                tag.push_str(&subtag.to_ascii_uppercase());
+            }
+            _ => tag.push_str(&subtag.to_ascii_lowercase()),
+        }
+    }
+
+    Some(tag)
+}
+
+// Drop trailing subtags until the tag fits the column ("zh-Hant-TW" -> "zh-Hant")
+pub fn fit_column(tag: &str) -> String {
+    let mut tag = tag;
+    while tag.len() > MAX_LANGUAGE_LENGTH {
+        match tag.rfind('-') {
+            Some(pos) => tag = &tag[..pos],
+            None => return tag[..MAX_LANGUAGE_LENGTH].to_string(),
+        }
+    }
+    tag.to_string()
+}
+
+// The value for a language column: the normalized tag, or the raw value cut to fit when
+// it isn't a recognizable language
+pub fn column_value(raw: &str) -> String {
+    let raw = raw.trim();
+    match normalize(raw) {
+        Some(tag) => fit_column(&tag),
+        None => utils::truncate_string(raw, MAX_LANGUAGE_LENGTH),
+    }
+}
@@ -0,0 +1,123 @@
+use std::env;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::models::Diagnostic;
+use crate::parser_state::ParserState;
+
+/*
+* Bounds on how much of a feed the parser will hold on to. Exceeding the payload size,
+* depth, item count or element count stops the parse there and the feed is emitted as
+* partial; over-long text and extra attributes are cut off and parsing carries on.
+* Running past the timeout fails the feed.
+*/
+#[derive(Debug, Clone)]
+pub struct ParseLimits {
+    // bytes read after the header lines
+    pub max_payload_bytes: usize,
+    // element nesting
+    pub max_depth: usize,
+    // bytes of text inside a single element
+    pub max_text_length: usize,
+    // item / entry elements
+    pub max_items: usize,
+    // elements of any kind in the document
+    pub max_elements: usize,
+    // attributes on a single element
+    pub max_attributes: usize,
+    // wall-clock time for the whole feed; a feed that runs over fails with no output
+    pub timeout: Duration,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_payload_bytes: 64 * 1024 * 1024,
+            max_depth: 64,
+            max_text_length: 1024 * 1024,
+            max_items: 50_000,
+            max_elements: 2_000_000,
+            max_attributes: 64,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ParseLimits {
+    // The defaults, each overridable with a FEEDPARSER_MAX_* (or FEEDPARSER_TIMEOUT_SECS) environment variable
+    pub fn from_env() -> ParseLimits {
+        let defaults = ParseLimits::default();
+        ParseLimits {
+            max_payload_bytes: env_limit("FEEDPARSER_MAX_PAYLOAD_BYTES", defaults.max_payload_bytes),
+            max_depth: env_limit("FEEDPARSER_MAX_DEPTH", defaults.max_depth),
+            max_text_length: env_limit("FEEDPARSER_MAX_TEXT_LENGTH", defaults.max_text_length),
+            max_items: env_limit("FEEDPARSER_MAX_ITEMS", defaults.max_items),
+            max_elements: env_limit("FEEDPARSER_MAX_ELEMENTS", defaults.max_elements),
+            max_attributes: env_limit("FEEDPARSER_MAX_ATTRIBUTES", defaults.max_attributes),
+            timeout: Duration::from_secs(env_limit("FEEDPARSER_TIMEOUT_SECS", defaults.timeout.as_secs() as usize) as u64),
+        }
+    }
+}
+
+fn env_limit(name: &str, default: usize) -> usize {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Limit {
+    PayloadSize,
+    Depth,
+    TextLength,
+    ItemCount,
+    ElementCount,
+    AttributeCount,
+}
+
+impl Limit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Limit::PayloadSize => "payload_size",
+            Limit::Depth => "depth",
+            Limit::TextLength => "text_length",
+            Limit::ItemCount => "item_count",
+            Limit::ElementCount => "element_count",
+            Limit::AttributeCount => "attribute_count",
+        }
+    }
+}
+
+// Note a limit on the state; each limit is reported once per feed
+pub fn record(state: &mut ParserState, limit: Limit) {
+    let element = state.current_element.clone();
+    record_at(state, limit, &element);
+}
+
+// Same as record, naming the element (or JSON key) where the limit was hit
+pub fn record_at(state: &mut ParserState, limit: Limit, element: &str) {
+    if state.limits_hit.contains(&limit) {
+        return;
+    }
+    state.limits_hit.push(limit);
+    state.diagnostics.push(Diagnostic {
+        code: "limit_exceeded".to_string(),
+        element: element.to_string(),
+        value: limit.as_str().to_string(),
+    });
+}
+
+// The longest prefix of text that fits in max bytes without splitting a character
+pub fn truncate_bytes(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
@@ -1,29 +1,68 @@
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Read};
 use std::io::Cursor;
 use std::sync::atomic::AtomicUsize;
 use std::sync::OnceLock;
 use std::path::{PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH, Instant};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, SystemTime, UNIX_EPOCH, Instant};
 use xml::reader::{XmlEvent, ParserConfig};
 use xml::name::OwnedName;
 
+mod dates;
+mod duplicates;
+mod durations;
+mod encoding;
+mod hashes;
+mod html;
+mod incremental;
+mod json_feed;
+mod languages;
+mod limits;
+mod mime;
 mod parser_state;
+mod recovery;
+mod state_store;
+mod summary;
 mod models;
+mod options;
 mod tags;
 mod outputs;
 #[cfg(test)]
 mod tests;
+mod urls;
 mod utils;
+use incremental::Incremental;
+use limits::{Limit, ParseLimits};
+use models::{Diagnostic, FeedOutcome, FeedStatus, SkipReason};
+use options::FeedOptions;
+use state_store::FeedIndex;
 use parser_state::ParserState;
+use summary::FeedRun;
 
 // Global counter initialized to zero at program start
 pub(crate) static GLOBAL_COUNTER: AtomicUsize = AtomicUsize::new(0);
 // Per-run output subfolder based on startup UNIX timestamp
 pub(crate) static OUTPUT_SUBDIR: OnceLock<PathBuf> = OnceLock::new();
+// How long process_feed waits past a feed's own deadline before giving up on it
+const TIMEOUT_GRACE: Duration = Duration::from_secs(5);
 
-#[tokio::main]
-async fn main() {
+fn main() {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start the async runtime: {}", e);
+            return;
+        }
+    };
+    runtime.block_on(run());
+    // A feed given up on at its timeout may still be stuck on a blocking thread. Dropping
+    // the runtime would wait for it, so leave it behind; exiting ends it.
+    runtime.shutdown_background();
+}
+
+async fn run() {
     // Track total runtime for the entire program
     let program_start = Instant::now();
     // Establish a stable per-run timestamped subfolder under outputs
@@ -47,6 +86,8 @@ async fn main() {
         }
     };
 
+    let mut runs = Vec::new();
+
     //Process each XML input file in parallel (asynchronously)
     for entry in entries {
         let entry = match entry {
@@ -90,10 +131,7 @@ async fn main() {
                 .unwrap_or("");
             let mut parts = stem.splitn(2, '_');
             let id_part = parts.next().unwrap_or("");
-            match id_part.parse::<i64>() {
-                Ok(v) => Some(v),
-                Err(_) => None,
-            }
+            id_part.parse::<i64>().ok()
         };
 
         match File::open(&path) {
@@ -102,8 +140,28 @@ async fn main() {
                 // Measure processing time per file
                 let start = Instant::now();
                 // Run feed processing asynchronously
-                process_feed(reader, file_name.clone(), feed_id).await;
-                println!("Processed {} in {:?}", file_name, start.elapsed());
+                let outcome = process_feed(reader, file_name.clone(), feed_id).await;
+                match (outcome.status, &outcome.error) {
+                    (FeedStatus::Complete, _) => println!("Processed {} in {:?}", file_name, start.elapsed()),
+                    (status, Some(error)) => println!(
+                        "Processed {} in {:?} ({}: {})",
+                        file_name,
+                        start.elapsed(),
+                        status.as_str(),
+                        error
+                    ),
+                    (status, None) => println!("Processed {} in {:?} ({})", file_name, start.elapsed(), status.as_str()),
+                }
+                if !outcome.limits_hit.is_empty() {
+                    let hit: Vec<&str> = outcome.limits_hit.iter().map(Limit::as_str).collect();
+                    println!("  limits hit: {}", hit.join(", "));
+                }
+                runs.push(FeedRun {
+                    source_name: file_name,
+                    feed_id,
+                    elapsed: start.elapsed(),
+                    outcome,
+                });
             }
             Err(e) => {
                 eprintln!("Unable to open file '{}': {}", path.display(), e);
@@ -112,12 +170,130 @@ async fn main() {
         }
     }
 
+    // The run's summary sits beside its output folder, as outputs/<timestamp>.summary.json
+    let summary = summary::build(&runs, startup_unix, program_start.elapsed());
+    let summary_path = PathBuf::from("outputs").join(format!("{}.summary.json", startup_unix));
+    if let Err(e) = summary::write(&summary, &summary_path) {
+        eprintln!("Failed to write run summary '{}': {}", summary_path.display(), e);
+    }
+
     // Print total runtime just before exiting
     println!("Total runtime: {:?}", program_start.elapsed());
 }
 
-// Synchronous parser implementation (unchanged logic)
-fn process_feed_sync<R: Read>(reader: R, _source_name: &str, feed_id: Option<i64>) {
+// Synchronous parser implementation using the options configured for this run
+fn process_feed_sync<R: Read>(reader: R, source_name: &str, feed_id: Option<i64>) -> FeedOutcome {
+    process_feed_sync_with_options(reader, source_name, feed_id, options::configured())
+}
+
+/*
+* Parse one feed in isolation: everything it writes, and its state store update, is held
+* back and only written out when it finishes. A feed that panics or runs past
+* limits.timeout is reported as failed and its records are thrown away.
+*/
+fn process_feed_sync_with_options<R: Read>(
+    reader: R,
+    source_name: &str,
+    feed_id: Option<i64>,
+    options: &FeedOptions,
+) -> FeedOutcome {
+    let limits = &options.limits;
+    let deadline = Instant::now() + limits.timeout;
+    outputs::start_buffering();
+
+    let mut reader = CountingReader { inner: reader, count: 0 };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        parse_feed(&mut reader, source_name, feed_id, options, deadline)
+    }));
+    let mut outcome = match result {
+        Ok(outcome) => outcome,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            FeedOutcome {
+                status: FeedStatus::Failed,
+                error: Some(format!("panicked: {}", message)),
+                ..Default::default()
+            }
+        }
+    };
+
+    // process_feed stops waiting at the deadline, so nothing may be written after it
+    if outcome.status != FeedStatus::Failed && Instant::now() > deadline {
+        outcome.status = FeedStatus::Failed;
+        outcome.error = Some(timeout_message(limits));
+    }
+
+    outcome.bytes_read = reader.count;
+
+    let failed = outcome.status == FeedStatus::Failed;
+    if failed {
+        // nothing the feed produced is kept
+        outcome.items_emitted = 0;
+        outcome.items_skipped.clear();
+        outputs::discard_buffered();
+    } else {
+        outputs::flush_buffered();
+    }
+    if let (Some(store), Some(feed_id)) = (&options.state_store, feed_id) {
+        if failed {
+            store.discard(feed_id);
+        } else {
+            store.commit(feed_id);
+        }
+    }
+    outcome
+}
+
+fn timeout_message(limits: &ParseLimits) -> String {
+    format!("timed out after {:?}", limits.timeout)
+}
+
+// Counts the bytes a feed's reader hands out, for the run summary
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+// What a finished parse reports back: its status and what became of its items
+fn outcome(state: ParserState) -> FeedOutcome {
+    let mut items_skipped = BTreeMap::new();
+    for item in &state.skipped_items {
+        *items_skipped.entry(item.reason).or_default() += 1;
+    }
+    if state.duplicate_items > 0 {
+        items_skipped.insert(SkipReason::Duplicate, state.duplicate_items);
+    }
+    FeedOutcome {
+        status: state.parse_status,
+        error: Some(state.parse_error).filter(|e| !e.is_empty()),
+        limits_hit: state.limits_hit,
+        items_emitted: state.items_written,
+        items_skipped,
+        ..Default::default()
+    }
+}
+
+fn parse_feed<R: Read>(
+    reader: R,
+    _source_name: &str,
+    feed_id: Option<i64>,
+    options: &FeedOptions,
+    deadline: Instant,
+) -> FeedOutcome {
+    let limits = &options.limits;
+
     // Wrap in a BufReader so we can read header lines and then pass the same reader to the XML parser
     let mut buf_reader = BufReader::new(reader);
 
@@ -142,28 +318,62 @@ fn process_feed_sync<R: Read>(reader: R, _source_name: &str, feed_id: Option<i64
     let feed_url_str = read_line_trim(&mut buf_reader);
     let downloaded_str = read_line_trim(&mut buf_reader);
 
-    // Parse optional metadata (currently not used in SQL output; reserved for future use)
-    let _last_modified_unix: Option<i64> = last_modified_str
+    // Parse optional metadata; a Last-Modified of 0 means the server didn't send one
+    let last_modified_unix: Option<i64> = last_modified_str
         .as_deref()
-        .and_then(|s| s.parse::<i64>().ok());
-    let _etag_opt: Option<String> = etag_str.as_deref().and_then(|s| {
+        .and_then(|s| s.parse::<i64>().ok())
+        .filter(|t| *t > 0);
+    let etag_opt: Option<String> = etag_str.as_deref().and_then(|s| {
         if s == "[[NO_ETAG]]" || s.is_empty() {
             None
         } else {
             Some(s.to_string())
         }
     });
-    let _feed_url_opt: Option<String> = feed_url_str.filter(|s| !s.is_empty());
+    let feed_url_opt: Option<String> = feed_url_str.filter(|s| !s.is_empty());
     let _downloaded_unix: Option<i64> = downloaded_str
         .as_deref()
         .and_then(|s| s.parse::<i64>().ok());
 
-    // After headers, read the remaining payload to determine if XML content exists
+    let previous_index = match (&options.state_store, feed_id) {
+        (Some(store), Some(feed_id)) => store.load(feed_id),
+        _ => None,
+    };
+
+    // Skip the payload when the server says nothing changed since the last complete parse
+    if previous_index
+        .as_ref()
+        .is_some_and(|index| index.unchanged(etag_opt.as_deref(), last_modified_unix))
+    {
+        outputs::write_unchanged(feed_id, etag_opt.as_deref(), last_modified_unix);
+        return FeedOutcome {
+            status: FeedStatus::Unchanged,
+            ..Default::default()
+        };
+    }
+
+    // Incremental mode diffs the items against what the state store kept from the last run
+    let new_incremental = || {
+        options
+            .incremental
+            .then(|| Incremental::new(previous_index.clone().unwrap_or_default()))
+    };
+    let validators = (etag_opt, last_modified_unix);
+
+    // After headers, read the remaining payload to determine if XML content exists.
+    // One byte past the limit is read to tell a payload that fits from one that doesn't.
     let mut xml_bytes: Vec<u8> = Vec::new();
-    if let Err(e) = buf_reader.read_to_end(&mut xml_bytes) {
+    let max_payload = limits.max_payload_bytes as u64;
+    if let Err(e) = buf_reader.take(max_payload.saturating_add(1)).read_to_end(&mut xml_bytes) {
         eprintln!("Failed to read XML payload after headers: {}", e);
-        return;
+        return FeedOutcome {
+            status: FeedStatus::Failed,
+            error: Some(e.to_string()),
+            ..Default::default()
+        };
     }
+    let payload_truncated = xml_bytes.len() > limits.max_payload_bytes;
+    xml_bytes.truncate(limits.max_payload_bytes);
 
     // Check if payload is empty or whitespace-only
     let has_non_whitespace = xml_bytes
@@ -172,20 +382,212 @@ fn process_feed_sync<R: Read>(reader: R, _source_name: &str, feed_id: Option<i64
 
     if !has_non_whitespace {
         // XML payload is empty or whitespace-only: emit a single newsfeeds row matching partytime shape
-        let state = ParserState::default();
-        outputs::write_newsfeeds(&state, feed_id);
-        return;
+        let mut state = ParserState::default();
+        outputs::write_newsfeeds(&mut state, feed_id);
+        return FeedOutcome::default();
     }
 
-    // Create an XML parser from the buffered payload
-    let cursor = Cursor::new(xml_bytes);
-    let config = ParserConfig::new();
-    let config = utils::add_html_entities_to_parser_config(config);
-    let parser = config.create_reader(cursor);
+    // JSON Feed payloads are mapped directly without going through the XML parser
+    if json_feed::is_json_payload(&xml_bytes) {
+        // a cut-off JSON document can't be read at all
+        if payload_truncated {
+            return FeedOutcome {
+                status: FeedStatus::Failed,
+                error: Some("payload exceeds the size limit".to_string()),
+                limits_hit: vec![Limit::PayloadSize],
+                ..Default::default()
+            };
+        }
+
+        // JSON Feed is always UTF-8
+        let mut state = ParserState {
+            feed_url: feed_url_opt.unwrap_or_default(),
+            encoding_detected: "UTF-8".to_string(),
+            incremental: new_incremental(),
+            ..Default::default()
+        };
+        let result = json_feed::process(&xml_bytes, limits, deadline, feed_id, &mut state);
+        if let Err(ParseStop::Error(error)) = result {
+            eprintln!("Error parsing JSON feed: {}", error);
+            return FeedOutcome {
+                status: FeedStatus::Failed,
+                error: Some(error),
+                ..Default::default()
+            };
+        }
+        return finish_parse(result, state, feed_id, options, previous_index, validators);
+    }
+
+    // Non-UTF-8 feeds are transcoded up front so handlers only ever see UTF-8 text
+    let (xml_bytes, encoding_info) = encoding::transcode_to_utf8(&xml_bytes);
+
+    let config = utils::add_html_entities_to_parser_config(ParserConfig::new());
 
     // Parser state holds all flags and accumulators used by handlers
-    let mut state = ParserState::default();
+    let new_state = || {
+        let mut state = ParserState {
+            feed_url: feed_url_opt.clone().unwrap_or_default(),
+            encoding_detected: encoding_info.detected.clone(),
+            encoding_declared: encoding_info.declared.clone(),
+            incremental: new_incremental(),
+            ..Default::default()
+        };
+        if payload_truncated {
+            limits::record(&mut state, Limit::PayloadSize);
+        }
+        state
+    };
+    let mut state = new_state();
+
+    let mut result = parse_xml(&xml_bytes, payload_truncated, &config, limits, deadline, feed_id, &mut state);
+
+    // A limit stop is deliberate, so only parser errors get a second attempt
+    if matches!(result, Err(ParseStop::Error(_))) {
+        // Retry once with the common mistakes cleaned up, starting from scratch
+        let text = String::from_utf8_lossy(&xml_bytes);
+        let (cleaned, fixes) = recovery::clean(&text, &config.extra_entities);
+        if !fixes.is_empty() {
+            // drop whatever the first attempt wrote
+            outputs::start_buffering();
+            state = new_state();
+            state.parse_status = FeedStatus::Recovered;
+            for fix in fixes {
+                state.diagnostics.push(Diagnostic {
+                    code: "xml_recovered".to_string(),
+                    element: String::new(),
+                    value: fix.to_string(),
+                });
+            }
+            result = parse_xml(cleaned.as_bytes(), payload_truncated, &config, limits, deadline, feed_id, &mut state);
+        }
+    }
+
+    finish_parse(result, state, feed_id, options, previous_index, validators)
+}
+
+/*
+* Turn how a parse ended into the feed's outcome. A parse that stopped early inside a
+* channel still emits that channel as partial; one that never reached a channel fails.
+*/
+fn finish_parse(
+    result: Result<(), ParseStop>,
+    mut state: ParserState,
+    feed_id: Option<i64>,
+    options: &FeedOptions,
+    previous_index: Option<FeedIndex>,
+    validators: (Option<String>, Option<i64>),
+) -> FeedOutcome {
+    let limits = &options.limits;
+
+    if let Err(ParseStop::Timeout) = result {
+        return FeedOutcome {
+            status: FeedStatus::Failed,
+            error: Some(timeout_message(limits)),
+            limits_hit: state.limits_hit,
+            ..Default::default()
+        };
+    }
+
+    if let Err(stop) = result {
+        let error = match stop {
+            ParseStop::Error(error) => {
+                eprintln!("Error parsing XML: {}", error);
+                state.diagnostics.push(Diagnostic {
+                    code: "xml_error".to_string(),
+                    element: state.current_element.clone(),
+                    value: error.clone(),
+                });
+                state.parse_error = error.clone();
+                error
+            }
+            // already recorded where it was hit
+            ParseStop::Limit(limit) => {
+                eprintln!("Stopped parsing at the {} limit", limit.as_str());
+                format!("stopped at the {} limit", limit.as_str())
+            }
+            ParseStop::Timeout => timeout_message(limits),
+        };
+
+        // Nothing to salvage when no channel was reached (an HTML error page, plain text):
+        // an empty newsfeeds row would overwrite what's known about the feed
+        if !state.channel_opened {
+            return FeedOutcome {
+                status: FeedStatus::Failed,
+                error: Some(error),
+                limits_hit: state.limits_hit,
+                ..Default::default()
+            };
+        }
+
+        // Emit the channel from whatever was collected before the stop
+        if !state.newsfeeds_written {
+            state.parse_status = FeedStatus::Partial;
+            state.in_channel = true;
+            tags::channel::on_end(feed_id, &mut state);
+        }
+    }
+
+    stage_state(options, feed_id, &state, previous_index, validators);
+    outcome(state)
+}
 
+/*
+* Hand what this run learned about the feed to the state store, to be kept if the feed
+* succeeds. The header's ETag and Last-Modified are only remembered for a complete parse
+* that wrote a newsfeeds record, so a partial or empty feed is parsed again next time even
+* if the server reports no change.
+*/
+fn stage_state(
+    options: &FeedOptions,
+    feed_id: Option<i64>,
+    state: &ParserState,
+    previous: Option<FeedIndex>,
+    (etag, last_modified): (Option<String>, Option<i64>),
+) {
+    let (store, feed_id) = match (&options.state_store, feed_id) {
+        (Some(store), Some(feed_id)) => (store, feed_id),
+        _ => return,
+    };
+
+    let complete = state.parse_status != FeedStatus::Partial && state.newsfeeds_written;
+    let previous = previous.unwrap_or_default();
+    let mut index = match &state.incremental {
+        Some(incremental) => incremental.index(complete),
+        None => FeedIndex {
+            items: previous.items,
+            ..Default::default()
+        },
+    };
+    if complete {
+        index.etag = etag;
+        index.last_modified = last_modified;
+    } else {
+        index.etag = previous.etag;
+        index.last_modified = previous.last_modified;
+    }
+    store.stage(feed_id, index);
+}
+
+// Why parse_xml (or json_feed::process) finished before the end of the document
+pub(crate) enum ParseStop {
+    // the parser's error message
+    Error(String),
+    Limit(Limit),
+    // ran past the feed's deadline
+    Timeout,
+}
+
+// Run one XML payload through the tag handlers, within the given limits. `truncated` says the
+// payload was cut at the size limit, so running out of input is that limit rather than an error.
+fn parse_xml(
+    xml_bytes: &[u8],
+    truncated: bool,
+    config: &ParserConfig,
+    limits: &ParseLimits,
+    deadline: Instant,
+    feed_id: Option<i64>,
+    state: &mut ParserState,
+) -> Result<(), ParseStop> {
     fn get_prefixed_name(name: &OwnedName) -> String {
         let prefix = name.prefix.clone();
         let local_name = name.local_name.clone();
@@ -203,64 +605,152 @@ fn process_feed_sync<R: Read>(reader: R, _source_name: &str, feed_id: Option<i64
             || matches!(name.namespace.as_deref(), Some("http://www.w3.org/2005/Atom")
         ) {
             format!("atom:{}", local_name)
-        } else if prefix.is_some() {
-            format!("{}:{}", prefix.unwrap(), local_name)
+        } else if prefix.as_deref() == Some("media")
+            || matches!(name.namespace.as_deref(), Some("http://search.yahoo.com/mrss/"))
+            || matches!(name.namespace.as_deref(), Some("http://search.yahoo.com/mrss"))
+        {
+            format!("media:{}", local_name)
+        } else if prefix.as_deref() == Some("googleplay")
+            || matches!(name.namespace.as_deref(), Some("http://www.google.com/schemas/play-podcasts/1.0"))
+            || matches!(name.namespace.as_deref(), Some("https://www.google.com/schemas/play-podcasts/1.0"))
+        {
+            format!("googleplay:{}", local_name)
+        } else if prefix.as_deref() == Some("spotify")
+            || matches!(name.namespace.as_deref(), Some("http://www.spotify.com/ns/rss"))
+            || matches!(name.namespace.as_deref(), Some("https://www.spotify.com/ns/rss"))
+        {
+            format!("spotify:{}", local_name)
+        } else if prefix.as_deref() == Some("rdf")
+            || matches!(name.namespace.as_deref(), Some("http://www.w3.org/1999/02/22-rdf-syntax-ns#"))
+        {
+            format!("rdf:{}", local_name)
+        } else if prefix.as_deref() == Some("dc")
+            || matches!(name.namespace.as_deref(), Some("http://purl.org/dc/elements/1.1/"))
+        {
+            format!("dc:{}", local_name)
+        } else if let Some(prefix) = prefix {
+            format!("{}:{}", prefix, local_name)
         } else {
             local_name
         }
     }
 
     // Parse the XML document
+    let parser = config.clone().create_reader(Cursor::new(xml_bytes));
+    let mut elements = 0;
+    let mut items = 0;
+    // text seen since the last tag, so one element can't grow past max_text_length
+    let mut text_length = 0;
+
     for event in parser {
+        if Instant::now() > deadline {
+            return Err(ParseStop::Timeout);
+        }
+
         match event {
             //A tag is opened.
-            Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+            Ok(XmlEvent::StartElement { name, mut attributes, .. }) => {
                 state.current_element = get_prefixed_name(&name);
-                let current = state.current_element.clone();
-                tags::dispatch_start(&current, &attributes, &mut state);
-            }
+                text_length = 0;
+
+                elements += 1;
+                if elements > limits.max_elements {
+                    limits::record(state, Limit::ElementCount);
+                    return Err(ParseStop::Limit(Limit::ElementCount));
+                }
+                if state.element_depth >= limits.max_depth {
+                    limits::record(state, Limit::Depth);
+                    return Err(ParseStop::Limit(Limit::Depth));
+                }
+                if matches!(state.current_element.as_str(), "item" | "atom:entry") {
+                    items += 1;
+                    if items > limits.max_items {
+                        limits::record(state, Limit::ItemCount);
+                        return Err(ParseStop::Limit(Limit::ItemCount));
+                    }
+                }
+                if attributes.len() > limits.max_attributes {
+                    limits::record(state, Limit::AttributeCount);
+                    attributes.truncate(limits.max_attributes);
+                }
 
-            //Text is found.
-            Ok(XmlEvent::Characters(data)) => {
                 let current = state.current_element.clone();
-                tags::dispatch_text(&current, &data, &mut state);
+                tags::dispatch_start(&current, &attributes, state);
             }
 
-            // CDATA is also textual content — treat it the same as Characters
-            Ok(XmlEvent::CData(data)) => {
+            //Text is found. CDATA is also textual content — treat it the same as Characters
+            Ok(XmlEvent::Characters(data)) | Ok(XmlEvent::CData(data)) => {
+                let allowed = limits.max_text_length.saturating_sub(text_length);
+                if data.len() > allowed {
+                    limits::record(state, Limit::TextLength);
+                }
+                let data = limits::truncate_bytes(&data, allowed);
+                text_length += data.len();
+                if data.is_empty() {
+                    continue;
+                }
+
                 let current = state.current_element.clone();
-                tags::dispatch_text(&current, &data, &mut state);
+                tags::dispatch_text(&current, data, state);
             }
 
             //A tag is closed.
             Ok(XmlEvent::EndElement { name }) => {
                 state.current_element = get_prefixed_name(&name);
+                text_length = 0;
                 let current = state.current_element.clone();
-                tags::dispatch_end(&current, feed_id, &mut state);
+                tags::dispatch_end(&current, feed_id, state);
             }
 
             //An error occurred.
-            Err(e) => {
-                eprintln!("Error parsing XML: {}", e);
-                break;
-            }
+            Err(e) if truncated && is_end_of_input(&e) => return Err(ParseStop::Limit(Limit::PayloadSize)),
+            Err(e) => return Err(ParseStop::Error(e.to_string())),
             _ => {}
         }
     }
+    Ok(())
+}
+
+// Whether the parser failed because the document ended before it was complete
+fn is_end_of_input(error: &xml::reader::Error) -> bool {
+    match error.kind() {
+        xml::reader::ErrorKind::UnexpectedEof => true,
+        xml::reader::ErrorKind::Syntax(message) => {
+            message.starts_with("Unexpected end of stream") || message.starts_with("Unclosed <![CDATA[")
+        }
+        _ => false,
+    }
 }
 
 // Public async wrapper that executes the synchronous parser on a blocking thread
-async fn process_feed<R>(reader: R, source_name: String, feed_id: Option<i64>)
+async fn process_feed<R>(reader: R, source_name: String, feed_id: Option<i64>) -> FeedOutcome
 where
     R: Read + Send + 'static,
 {
-    // Ignore the JoinError here but log if it occurs
+    // Panics and timeouts inside the parser come back as failed outcomes. The extra wait
+    // covers a feed stuck somewhere the parser can't check its deadline, like a read;
+    // its thread is left behind but can no longer write anything, and main doesn't wait
+    // for it when the run ends.
+    let limits = &options::configured().limits;
     let source_for_task = source_name.clone();
-    if let Err(e) = tokio::task::spawn_blocking(move || {
-        process_feed_sync(reader, &source_for_task, feed_id);
-    })
-        .await
-    {
-        eprintln!("Error in async processing for '{}': {}", source_name, e);
+    let task = tokio::task::spawn_blocking(move || process_feed_sync(reader, &source_for_task, feed_id));
+    match tokio::time::timeout(limits.timeout + TIMEOUT_GRACE, task).await {
+        Ok(Ok(outcome)) => outcome,
+        Err(_) => {
+            eprintln!("Timed out processing '{}'", source_name);
+            FeedOutcome {
+                status: FeedStatus::Failed,
+                error: Some(timeout_message(limits)),
+                ..Default::default()
+            }
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error in async processing for '{}': {}", source_name, e);
+            FeedOutcome {
+                status: FeedStatus::Failed,
+                error: Some(e.to_string()),
+                ..Default::default()
+            }
+        }
     }
 }
\ No newline at end of file
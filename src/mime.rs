@@ -0,0 +1,197 @@
+use url::Url;
+
+pub const HLS: &str = "application/vnd.apple.mpegurl";
+pub const DASH: &str = "application/dash+xml";
+
+// File extension (lowercase, without the dot) to MIME type
+const EXTENSIONS: &[(&str, &str)] = &[
+    // audio
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("m4b", "audio/mp4"),
+    ("aac", "audio/aac"),
+    ("opus", "audio/opus"),
+    ("ogg", "audio/ogg"),
+    ("oga", "audio/ogg"),
+    ("flac", "audio/flac"),
+    ("wav", "audio/wav"),
+    ("aif", "audio/aiff"),
+    ("aiff", "audio/aiff"),
+    ("wma", "audio/x-ms-wma"),
+    // video
+    ("mp4", "video/mp4"),
+    ("m4v", "video/mp4"),
+    ("mov", "video/quicktime"),
+    ("avi", "video/avi"),
+    ("wmv", "video/x-ms-wmv"),
+    ("webm", "video/webm"),
+    ("mkv", "video/x-matroska"),
+    ("ogv", "video/ogg"),
+    ("3gp", "video/3gpp"),
+    // streaming manifests
+    ("m3u8", HLS),
+    ("mpd", DASH),
+    // documents
+    ("pdf", "application/pdf"),
+    ("epub", "application/epub+zip"),
+];
+
+// Misspelled or legacy types mapped to the registered one
+const ALIASES: &[(&str, &str)] = &[
+    ("audio/mp3", "audio/mpeg"),
+    ("audio/mpeg3", "audio/mpeg"),
+    ("audio/mpg", "audio/mpeg"),
+    ("audio/x-mp3", "audio/mpeg"),
+    ("audio/x-mpeg", "audio/mpeg"),
+    ("audio/x-mpeg-3", "audio/mpeg"),
+    ("audio/x-mpg", "audio/mpeg"),
+    ("audio/m4a", "audio/mp4"),
+    ("audio/x-m4a", "audio/mp4"),
+    ("audio/x-m4b", "audio/mp4"),
+    ("audio/x-mp4", "audio/mp4"),
+    ("audio/x-aac", "audio/aac"),
+    ("audio/x-flac", "audio/flac"),
+    ("audio/wave", "audio/wav"),
+    ("audio/x-wav", "audio/wav"),
+    ("audio/vnd.wave", "audio/wav"),
+    ("video/x-m4v", "video/mp4"),
+    ("video/x-mp4", "video/mp4"),
+    ("application/x-mpegurl", HLS),
+    ("audio/x-mpegurl", HLS),
+    ("audio/mpegurl", HLS),
+];
+
+// Declared types that say nothing about the content
+const GENERIC: &[&str] = &[
+    "application/octet-stream",
+    "binary/octet-stream",
+    "application/binary",
+    "application/download",
+    "application/force-download",
+    "application/x-download",
+    "application/unknown",
+];
+
+// MIME type for a URL from the extension of its path; query strings and hosts are ignored
+pub fn from_url(url: &str) -> Option<&'static str> {
+    let path = match Url::parse(url.trim()) {
+        Ok(parsed) => parsed.path().to_string(),
+        Err(_) => url.split(['?', '#']).next().unwrap_or("").to_string(),
+    };
+
+    let file_name = path.rsplit('/').next()?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+    let extension = extension.to_ascii_lowercase();
+
+    EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+}
+
+/*
+* Clean up a declared enclosure type:
+* - parameters are dropped and case is lowered ("Audio/MPEG; charset=x" -> "audio/mpeg")
+* - known misspellings map to the registered type ("audio/mp3" -> "audio/mpeg")
+* - missing, generic (application/octet-stream) or malformed types are replaced by the
+*   type the URL's extension implies, when there is one
+*/
+pub fn normalize(declared: &str, url: &str) -> String {
+    let base = declared
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+
+    if let Some((_, mime)) = ALIASES.iter().find(|(alias, _)| *alias == base) {
+        return mime.to_string();
+    }
+
+    let is_generic = base.is_empty() || !base.contains('/') || GENERIC.contains(&base.as_str());
+    if is_generic {
+        if let Some(mime) = from_url(url) {
+            return mime.to_string();
+        }
+    }
+
+    base
+}
+
+// Audio, video and streaming types, the ones that can back an episode
+pub fn is_playable(mime: &str) -> bool {
+    mime.starts_with("audio/") || mime.starts_with("video/") || mime == HLS || mime == DASH
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Audio,
+    Video,
+    Stream,
+    Document,
+    Other,
+}
+
+impl MediaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Audio => "audio",
+            MediaKind::Video => "video",
+            MediaKind::Stream => "stream",
+            MediaKind::Document => "document",
+            MediaKind::Other => "other",
+        }
+    }
+}
+
+// What an enclosure is, from its (normalized) MIME type
+pub fn media_kind(mime: &str) -> MediaKind {
+    if mime == HLS || mime == DASH {
+        MediaKind::Stream
+    } else if mime.starts_with("audio/") {
+        MediaKind::Audio
+    } else if mime.starts_with("video/") {
+        MediaKind::Video
+    } else if mime.starts_with("text/") || mime == "application/pdf" || mime == "application/epub+zip" {
+        MediaKind::Document
+    } else {
+        MediaKind::Other
+    }
+}
+
+// Container format for a (normalized) MIME type, empty when unknown
+pub fn container(mime: &str) -> &'static str {
+    match mime {
+        "audio/mpeg" => "mp3",
+        "audio/mp4" | "video/mp4" => "mp4",
+        "audio/aac" => "aac",
+        "audio/ogg" | "video/ogg" | "audio/opus" => "ogg",
+        "audio/flac" => "flac",
+        "audio/wav" => "wav",
+        "audio/aiff" => "aiff",
+        "audio/webm" | "video/webm" => "webm",
+        "video/x-matroska" | "audio/x-matroska" => "matroska",
+        "video/quicktime" => "quicktime",
+        "video/avi" | "video/x-msvideo" => "avi",
+        "audio/x-ms-wma" | "video/x-ms-wmv" => "asf",
+        "video/3gpp" | "audio/3gpp" => "3gp",
+        HLS => "hls",
+        DASH => "dash",
+        "application/pdf" => "pdf",
+        "application/epub+zip" => "epub",
+        _ => "",
+    }
+}
+
+// Most common kind among the items; ties go to the kind listed first in MediaKind
+pub fn dominant_kind(kinds: &[MediaKind]) -> Option<MediaKind> {
+    [MediaKind::Audio, MediaKind::Video, MediaKind::Stream, MediaKind::Document, MediaKind::Other]
+        .into_iter()
+        .map(|kind| (kind, kinds.iter().filter(|k| **k == kind).count()))
+        .filter(|(_, count)| *count > 0)
+        .fold(None, |best: Option<(MediaKind, usize)>, (kind, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((kind, count)),
+        })
+        .map(|(kind, _)| kind)
+}
@@ -1,9 +1,135 @@
+use std::collections::BTreeMap;
+
 use serde::Serialize;
 
+use crate::limits::Limit;
+
+// Declared explicit status; variants are ordered so the strictest source wins with max()
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplicitStatus {
+    #[default]
+    Unspecified,
+    Clean,
+    Explicit,
+}
+
+impl ExplicitStatus {
+    // itunes:explicit / googleplay:explicit values; anything unrecognized counts as not declared
+    pub fn from_value(value: &str) -> ExplicitStatus {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "explicit" | "1" => ExplicitStatus::Explicit,
+            "false" | "no" | "clean" | "0" => ExplicitStatus::Clean,
+            _ => ExplicitStatus::Unspecified,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExplicitStatus::Unspecified => "unspecified",
+            ExplicitStatus::Clean => "clean",
+            ExplicitStatus::Explicit => "explicit",
+        }
+    }
+
+    // Value for the legacy 0/1 explicit columns
+    pub fn flag(&self) -> i32 {
+        if *self == ExplicitStatus::Explicit { 1 } else { 0 }
+    }
+}
+
+// How far parsing a feed got
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedStatus {
+    #[default]
+    Complete,
+    // parsed after recovery::clean fixed the payload
+    Recovered,
+    // the parser gave up part way; only what came before the error was emitted
+    Partial,
+    // nothing could be read
+    Failed,
+    // skipped: the ETag or Last-Modified matched the last complete parse
+    Unchanged,
+}
+
+impl FeedStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedStatus::Complete => "complete",
+            FeedStatus::Recovered => "recovered",
+            FeedStatus::Partial => "partial",
+            FeedStatus::Failed => "failed",
+            FeedStatus::Unchanged => "unchanged",
+        }
+    }
+}
+
+// Result of processing one feed, reported back to main
+#[derive(Debug, Clone, Default)]
+pub struct FeedOutcome {
+    pub status: FeedStatus,
+    pub error: Option<String>,
+    // limits hit while parsing, in the order they were hit
+    pub limits_hit: Vec<Limit>,
+    // nfitems records written
+    pub items_emitted: usize,
+    pub items_skipped: BTreeMap<SkipReason, usize>,
+    // bytes taken from the reader, header lines included
+    pub bytes_read: u64,
+}
+
+// Why an item in the feed didn't become an nfitems record
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    // no enclosure, attachment or media:content at all
+    NoEnclosure,
+    // an enclosure with a blank URL
+    EmptyUrl,
+    // an enclosure URL with a scheme other than http or https
+    NonHttpEnclosure,
+    // an enclosure URL that can't be parsed or has no host
+    InvalidUrl,
+    // another copy of the same episode was kept
+    Duplicate,
+}
+
+// An item dropped for not having a usable enclosure, listed in the skipped_items output
+#[derive(Serialize, Clone)]
+pub struct SkippedItem {
+    pub title: String,
+    pub guid: String,
+    pub reason: SkipReason,
+}
+
+// Something in the feed we had to drop or couldn't read, surfaced in the diagnostics output
+#[derive(Serialize, Clone)]
+pub struct Diagnostic {
+    pub code: String,
+    pub element: String,
+    pub value: String,
+}
+
 #[derive(Serialize)]
 pub struct PodcastTranscript {
     pub url: String,
     pub r#type: String,
+    pub language: String,
+}
+
+#[derive(Serialize, Default)]
+pub struct PodcastAlternateEnclosure {
+    pub r#type: String,
+    pub length: String,
+    pub bitrate: String,
+    pub height: String,
+    pub lang: String,
+    pub title: String,
+    pub rel: String,
+    pub default: bool,
+    pub sources: Vec<String>,
 }
 
 #[derive(Serialize)]
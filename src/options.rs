@@ -0,0 +1,47 @@
+use std::env;
+use std::sync::OnceLock;
+
+use crate::limits::ParseLimits;
+use crate::state_store::StateStore;
+
+static CONFIGURED: OnceLock<FeedOptions> = OnceLock::new();
+
+// How feeds are processed in a run
+#[derive(Default)]
+pub struct FeedOptions {
+    pub limits: ParseLimits,
+    // per-feed state kept between runs; None keeps nothing
+    pub state_store: Option<StateStore>,
+    // write only new and changed items (and tombstones), diffed against state_store
+    pub incremental: bool,
+}
+
+impl FeedOptions {
+    /*
+    * Options from the environment:
+    * - FEEDPARSER_MAX_* / FEEDPARSER_TIMEOUT_SECS for the parse limits
+    * - FEEDPARSER_STATE_DIR to keep per-feed state (item hashes, ETag, Last-Modified) there
+    * - FEEDPARSER_INCREMENTAL=1 for incremental mode (state goes to "state" unless
+    *   FEEDPARSER_STATE_DIR says otherwise)
+    */
+    pub fn from_env() -> FeedOptions {
+        let incremental = env::var("FEEDPARSER_INCREMENTAL")
+            .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let state_dir = env::var("FEEDPARSER_STATE_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .or_else(|| incremental.then(|| "state".to_string()));
+
+        FeedOptions {
+            limits: ParseLimits::from_env(),
+            state_store: state_dir.map(StateStore::open),
+            incremental,
+        }
+    }
+}
+
+// Options for this run, read from the environment the first time they're needed
+pub fn configured() -> &'static FeedOptions {
+    CONFIGURED.get_or_init(FeedOptions::from_env)
+}
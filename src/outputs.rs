@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -6,6 +7,15 @@ use serde::{Serialize, Deserialize};
 use serde_json::Value as JsonValue;
 
 use crate::{parser_state::ParserState, OUTPUT_SUBDIR, GLOBAL_COUNTER};
+use crate::dates;
+use crate::duplicates::PendingItem;
+use crate::hashes;
+use crate::html;
+use crate::incremental::Incremental;
+use crate::languages;
+use crate::mime;
+use crate::models::{ExplicitStatus, SkipReason};
+use crate::urls::{self, UrlKind};
 use crate::utils;
 
 fn get_output_dir() -> PathBuf {
@@ -31,7 +41,67 @@ pub struct SqlInsert {
     pub feed_id: Option<i64>,
 }
 
+impl SqlInsert {
+    pub fn value(&self, column: &str) -> Option<&JsonValue> {
+        let index = self.columns.iter().position(|c| c == column)?;
+        self.values.get(index)
+    }
+
+    pub fn value_mut(&mut self, column: &str) -> Option<&mut JsonValue> {
+        let index = self.columns.iter().position(|c| c == column)?;
+        self.values.get_mut(index)
+    }
+}
+
+// (table name for the file, feed_id, serialized record)
+type BufferedRecord = (String, Option<i64>, String);
+
+thread_local! {
+    // Records held back while a feed is parsed, so a failed attempt can be thrown away.
+    // Each feed is parsed start to finish on one thread, so the buffer is per thread.
+    static BUFFERED: RefCell<Option<Vec<BufferedRecord>>> = const { RefCell::new(None) };
+}
+
+// Hold every record written on this thread until flush_buffered or discard_buffered
+pub fn start_buffering() {
+    BUFFERED.with(|buffered| *buffered.borrow_mut() = Some(Vec::new()));
+}
+
+// Write out the held records and go back to writing directly
+pub fn flush_buffered() {
+    let records = BUFFERED.with(|buffered| buffered.borrow_mut().take()).unwrap_or_default();
+    for (table_for_name, feed_id, serialized) in records {
+        write_file(&table_for_name, feed_id, &serialized);
+    }
+}
+
+// Drop the held records and go back to writing directly
+pub fn discard_buffered() {
+    BUFFERED.with(|buffered| *buffered.borrow_mut() = None);
+}
+
 fn write_record(record: &SqlInsert, table_for_name: &str) {
+    let serialized = match serde_json::to_string(record) {
+        Ok(serialized) => serialized,
+        Err(e) => {
+            eprintln!("Failed to serialize record for {}: {}", table_for_name, e);
+            return;
+        }
+    };
+
+    let unbuffered = BUFFERED.with(|buffered| match buffered.borrow_mut().as_mut() {
+        Some(records) => {
+            records.push((table_for_name.to_string(), record.feed_id, serialized));
+            None
+        }
+        None => Some(serialized),
+    });
+    if let Some(serialized) = unbuffered {
+        write_file(table_for_name, record.feed_id, &serialized);
+    }
+}
+
+fn write_file(table_for_name: &str, feed_id: Option<i64>, serialized: &str) {
     // Ensure directory exists
     let out_dir = get_output_dir();
     if let Err(e) = fs::create_dir_all(&out_dir) {
@@ -40,50 +110,99 @@ fn write_record(record: &SqlInsert, table_for_name: &str) {
 
     // Compute counter (1-based) and build filename
     let counter_val = GLOBAL_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
-    let fid_for_name = record
-        .feed_id
+    let fid_for_name = feed_id
         .map(|v| v.to_string())
         .unwrap_or_else(|| "NULL".to_string());
     let file_name = format!("{}_{}_{}.json", counter_val, table_for_name, fid_for_name);
     let file_path = out_dir.join(file_name);
 
-    match serde_json::to_string(record) {
-        Ok(serialized) => {
-            if let Err(e) = fs::write(&file_path, serialized) {
-                eprintln!("Failed to write {}: {}", file_path.display(), e);
-            }
-        }
-        Err(e) => {
-            eprintln!("Failed to serialize record for {}: {}", table_for_name, e);
-        }
+    if let Err(e) = fs::write(&file_path, serialized) {
+        eprintln!("Failed to write {}: {}", file_path.display(), e);
     }
 }
 
-pub fn write_newsfeeds(state: &ParserState, feed_id: Option<i64>) {
+// Item counts only exist in incremental mode
+fn incremental_count(state: &ParserState, count: impl Fn(&Incremental) -> usize) -> JsonValue {
+    match &state.incremental {
+        Some(incremental) => JsonValue::from(count(incremental)),
+        None => JsonValue::Null,
+    }
+}
+
+// any source marking the feed explicit wins
+fn channel_explicit_status(state: &ParserState) -> ExplicitStatus {
+    state.channel_explicit
+        .max(state.channel_googleplay_explicit)
+        .max(state.channel_media_explicit)
+}
+
+pub fn write_newsfeeds(state: &mut ParserState, feed_id: Option<i64>) {
 
     let title = utils::clean_string(&state.channel_title);
     let title = utils::truncate_string(&title, 768);
 
-    let link = utils::clean_string(&state.channel_link);
+    let link = urls::sanitize(&state.channel_link, UrlKind::Link, "link", &mut state.diagnostics);
 
     let description = if !state.channel_itunes_summary.is_empty() {
         state.channel_itunes_summary.as_str()
-    } else {
+    } else if !state.channel_description.is_empty() {
         state.channel_description.as_str()
+    } else if !state.channel_googleplay_description.is_empty() {
+        state.channel_googleplay_description.as_str()
+    } else {
+        state.channel_media_description.as_str()
+    };
+    let description_html = html::sanitize(description);
+    let description_text = html::to_text(&description_html);
+
+    let itunes_author = if !state.channel_itunes_author.is_empty() {
+        state.channel_itunes_author.as_str()
+    } else if !state.channel_googleplay_author.is_empty() {
+        state.channel_googleplay_author.as_str()
+    } else {
+        state.channel_dc_creator.as_str()
     };
 
+    let itunes_image = urls::sanitize(&state.channel_itunes_image, UrlKind::Media, "itunes_image", &mut state.diagnostics);
     let image = if !state.channel_image.is_empty() {
-        utils::sanitize_url(&state.channel_image)
+        urls::sanitize(&state.channel_image, UrlKind::Media, "image", &mut state.diagnostics)
+    } else if !state.channel_itunes_image.is_empty() {
+        itunes_image.clone()
+    } else if !state.channel_googleplay_image.is_empty() {
+        urls::sanitize(&state.channel_googleplay_image, UrlKind::Media, "image", &mut state.diagnostics)
+    } else if !state.channel_atom_icon.is_empty() {
+        urls::sanitize(&state.channel_atom_icon, UrlKind::Media, "image", &mut state.diagnostics)
     } else {
-        utils::sanitize_url(&state.channel_itunes_image)
+        urls::sanitize(&state.channel_media_thumbnail, UrlKind::Media, "image", &mut state.diagnostics)
     };
 
-    let itunes_new_feed_url = utils::sanitize_url(&state.channel_itunes_new_feed_url);
-    let itunes_image = utils::sanitize_url(&state.channel_itunes_image);
+    let explicit_status = channel_explicit_status(state);
 
-    let language = utils::truncate_string(&state.channel_language, 8);
+    let spotify_limit = state.channel_spotify_limit
+        .parse::<i32>()
+        .ok()
+        .map(utils::truncate_int);
+    let spotify_country_of_origin = utils::clean_string(&state.channel_spotify_country_of_origin)
+        .to_ascii_lowercase();
+
+    let itunes_new_feed_url = urls::sanitize(&state.channel_itunes_new_feed_url, UrlKind::Link, "itunes_new_feed_url", &mut state.diagnostics);
+
+    let language_raw = if !state.channel_language.trim().is_empty() {
+        state.channel_language.trim()
+    } else {
+        state.channel_dc_language.trim()
+    };
+    // values that aren't recognizable languages keep the old truncated form
+    let language = languages::column_value(language_raw);
+    let language_raw = utils::truncate_string(language_raw, 64);
     let item_count = utils::truncate_int(state.item_count);
 
+    // podcast:medium as declared; the dominant item kind is what consumers fall back to without it
+    let podcast_medium = utils::truncate_string(&state.channel_podcast_medium.trim().to_ascii_lowercase(), 32);
+    let dominant_media_kind = mime::dominant_kind(&state.item_media_kinds)
+        .map(|kind| kind.as_str())
+        .unwrap_or("");
+
     let podcast_owner = if !state.channel_podcast_owner.is_empty() {
         utils::truncate_string(&state.channel_podcast_owner, 255)
     } else {
@@ -91,23 +210,28 @@ pub fn write_newsfeeds(state: &ParserState, feed_id: Option<i64>) {
     };
 
     let current_time = now_ts();
-    let past_pub_dates: Vec<i64> = state.item_pubdates.clone()
+    let past_pub_dates: Vec<i64> = state.item_pubdates
         .iter()
         .filter(|&pub_date| *pub_date <= current_time)
-        .map(|&pub_date| pub_date)
+        .copied()
         .collect();
 
     let newest_pub_date: i64 = past_pub_dates.iter().max().copied().unwrap_or(0);
     let oldest_pub_date: i64 = past_pub_dates.iter().min().copied().unwrap_or(0);
     let update_frequency: i32 = utils::calculate_update_frequency(&past_pub_dates);
 
-    let final_pub_date = if state.channel_pub_date != 0 {
-        state.channel_pub_date
+    // dates derived from the items have no raw string of their own
+    let (final_pub_date, pub_date_raw) = if state.channel_pub_date != 0 {
+        (state.channel_pub_date, state.channel_pub_date_raw.as_str())
+    } else if state.channel_dc_date != 0 {
+        (state.channel_dc_date, state.channel_dc_date_raw.as_str())
     } else if state.channel_last_build_date != 0 {
-        state.channel_last_build_date
+        (state.channel_last_build_date, state.channel_last_build_date_raw.as_str())
     } else {
-        newest_pub_date
+        (newest_pub_date, "")
     };
+    let pub_date_offset = dates::offset_minutes(pub_date_raw);
+    let pub_date_raw = utils::truncate_string(pub_date_raw, 128);
 
     // get the first lightning value block, or fallback to first value if no lightning
     let podcast_value = state.channel_podcast_values
@@ -116,19 +240,23 @@ pub fn write_newsfeeds(state: &ParserState, feed_id: Option<i64>) {
         .cloned()
         .or_else(|| state.channel_podcast_values.first().cloned());
 
-    let record = SqlInsert {
+    let mut record = SqlInsert {
         table: "newsfeeds".to_string(),
         columns: vec![
             "feed_id".to_string(),
             "title".to_string(),
             "link".to_string(),
             "description".to_string(),
+            "description_html".to_string(),
+            "description_text".to_string(),
             "generator".to_string(),
             "itunes_author".to_string(),
             "type".to_string(),
             "explicit".to_string(),
+            "explicit_status".to_string(),
             "image".to_string(),
             "language".to_string(),
+            "language_raw".to_string(),
             "itunes_owner_name".to_string(),
             "itunes_owner_email".to_string(),
             "atom_author_name".to_string(),
@@ -141,28 +269,54 @@ pub fn write_newsfeeds(state: &ParserState, feed_id: Option<i64>) {
             "podcast_funding_url".to_string(),
             "podcast_funding_text".to_string(),
             "podcast_locked".to_string(),
+            "podcast_medium".to_string(),
+            "dominant_media_kind".to_string(),
             "podcast_value".to_string(),
             "podcast_owner".to_string(),
             "pubsub_hub_url".to_string(),
             "pubsub_self_url".to_string(),
             "pub_date".to_string(),
+            "pub_date_offset".to_string(),
+            "pub_date_raw".to_string(),
             "last_build_date".to_string(),
             "newest_item_pub_date".to_string(),
             "oldest_item_pub_date".to_string(),
             "item_count".to_string(),
             "update_frequency".to_string(),
+            "googleplay_block".to_string(),
+            "spotify_limit".to_string(),
+            "spotify_country_of_origin".to_string(),
+            "categories".to_string(),
+            "rights".to_string(),
+            "encoding_detected".to_string(),
+            "encoding_declared".to_string(),
+            "parse_status".to_string(),
+            "parse_error".to_string(),
+            "limits_hit".to_string(),
+            "items_added".to_string(),
+            "items_changed".to_string(),
+            "items_removed".to_string(),
+            "duplicate_items".to_string(),
+            "skipped_no_enclosure".to_string(),
+            "skipped_empty_url".to_string(),
+            "skipped_non_http_enclosure".to_string(),
+            "skipped_invalid_url".to_string(),
         ],
         values: vec![
             match feed_id { Some(v) => JsonValue::from(v), None => JsonValue::Null },
             JsonValue::from(title),
             JsonValue::from(link),
             JsonValue::from(description),
+            JsonValue::from(description_html),
+            JsonValue::from(description_text),
             JsonValue::from(state.channel_generator.clone()),
-            JsonValue::from(state.channel_itunes_author.clone()),
-            JsonValue::from(state.feed_type.clone()),
-            JsonValue::from(state.channel_explicit.clone()),
+            JsonValue::from(itunes_author),
+            JsonValue::from(state.feed_type),
+            JsonValue::from(explicit_status.flag()),
+            JsonValue::from(explicit_status.as_str()),
             JsonValue::from(image),
             JsonValue::from(language),
+            JsonValue::from(language_raw),
             JsonValue::from(state.channel_itunes_owner_name.clone()),
             JsonValue::from(state.channel_itunes_owner_email.clone()),
             JsonValue::from(state.channel_atom_author_name.clone()),
@@ -174,29 +328,55 @@ pub fn write_newsfeeds(state: &ParserState, feed_id: Option<i64>) {
             JsonValue::from(state.channel_podcast_guid.clone()),
             JsonValue::from(state.channel_podcast_funding_url.clone()),
             JsonValue::from(state.channel_podcast_funding_text.clone()),
-            JsonValue::from(state.channel_podcast_locked.clone()),
+            JsonValue::from(state.channel_podcast_locked),
+            JsonValue::from(podcast_medium),
+            JsonValue::from(dominant_media_kind),
             serde_json::to_value(&podcast_value).unwrap_or(JsonValue::Null),
             JsonValue::from(podcast_owner),
             JsonValue::from(state.channel_pubsub_hub_url.clone()),
             JsonValue::from(state.channel_pubsub_self_url.clone()),
             JsonValue::from(final_pub_date),
+            JsonValue::from(pub_date_offset),
+            JsonValue::from(pub_date_raw),
             JsonValue::from(state.channel_last_build_date),
             JsonValue::from(newest_pub_date),
             JsonValue::from(oldest_pub_date),
             JsonValue::from(item_count),
             JsonValue::from(update_frequency),
+            JsonValue::from(state.channel_googleplay_block),
+            JsonValue::from(spotify_limit),
+            JsonValue::from(spotify_country_of_origin),
+            JsonValue::from(state.channel_categories.clone()),
+            JsonValue::from(utils::clean_string(&state.channel_rights)),
+            JsonValue::from(state.encoding_detected.clone()),
+            JsonValue::from(utils::truncate_string(&state.encoding_declared, 64)),
+            JsonValue::from(state.parse_status.as_str()),
+            JsonValue::from(state.parse_error.clone()),
+            serde_json::to_value(&state.limits_hit).unwrap_or(JsonValue::Null),
+            incremental_count(state, |incremental| incremental.added),
+            incremental_count(state, |incremental| incremental.changed),
+            incremental_count(state, |incremental| incremental.removed.len()),
+            JsonValue::from(state.duplicate_items),
+            JsonValue::from(skipped_count(state, SkipReason::NoEnclosure)),
+            JsonValue::from(skipped_count(state, SkipReason::EmptyUrl)),
+            JsonValue::from(skipped_count(state, SkipReason::NonHttpEnclosure)),
+            JsonValue::from(skipped_count(state, SkipReason::InvalidUrl)),
         ],
         feed_id,
     };
+    let content_hash = hashes::channel_hash(&record.columns, &record.values);
+    record.columns.push("content_hash".to_string());
+    record.values.push(JsonValue::from(content_hash));
     write_record(&record, "newsfeeds");
 }
 
-pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
+// The nfitems record for the item that just closed; its content_hash is added in write_nfitems
+pub fn nfitems_record(state: &mut ParserState, feed_id: Option<i64>) -> SqlInsert {
     let title = utils::truncate_string(
         if !state.itunes_title.is_empty() {
             &state.itunes_title
         } else {
-            &state.title.trim()
+            state.title.trim()
         },
         1024,
     );
@@ -207,11 +387,18 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
         &state.content_encoded
     } else if !state.description.is_empty() {
         &state.description
-    } else {
+    } else if !state.itunes_summary.is_empty() {
         &state.itunes_summary
+    } else {
+        &state.media_description
     }.trim();
+    let description_html = html::sanitize(description);
+    let description_text = html::to_text(&description_html);
+
+    let link = urls::sanitize(&state.link, UrlKind::Link, "link", &mut state.diagnostics);
 
-    let link = utils::sanitize_url(&state.link);
+    let pub_date_offset = dates::offset_minutes(&state.pub_date_raw);
+    let pub_date_raw = utils::truncate_string(&state.pub_date_raw, 128);
 
     let guid = utils::truncate_string(
         if !state.guid.is_empty() {
@@ -224,7 +411,7 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
         740,
     );
 
-    let mut enclosure_url = utils::sanitize_url(&state.enclosure_url);
+    let mut enclosure_url = urls::sanitize(&state.enclosure_url, UrlKind::Media, "enclosure_url", &mut state.diagnostics);
 
     if enclosure_url.to_lowercase().contains("&amp;") {
         enclosure_url = enclosure_url.replace("&amp;", "&").to_string();
@@ -237,12 +424,10 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
         .unwrap_or(0)
         .min(922337203685477580);
 
-    let enclosure_type = if !state.enclosure_type.is_empty() {
-        utils::truncate_string(&state.enclosure_type, 128)
-    } else {
-        let guessed = utils::guess_enclosure_type(&state.enclosure_url);
-        utils::truncate_string(&guessed, 128)
-    };
+    let enclosure_type = utils::truncate_string(&state.enclosure_type, 128);
+    let enclosure_type_declared = utils::truncate_string(&state.enclosure_type_declared, 128);
+    let media_kind = mime::media_kind(&state.enclosure_type).as_str();
+    let container = mime::container(&state.enclosure_type);
 
     let itunes_season = state.itunes_season
         .parse::<i32>()
@@ -256,9 +441,22 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
     };
 
     let image = if !state.itunes_image.is_empty() {
-        utils::sanitize_url(&state.itunes_image)
+        urls::sanitize(&state.itunes_image, UrlKind::Media, "image", &mut state.diagnostics)
+    } else if !state.item_image.is_empty() {
+        urls::sanitize(&state.item_image, UrlKind::Media, "image", &mut state.diagnostics)
     } else {
-        utils::sanitize_url(&state.item_image)
+        urls::sanitize(&state.media_thumbnail, UrlKind::Media, "image", &mut state.diagnostics)
+    };
+
+    // any source marking the item explicit wins; the legacy column only reflects the item's own tags
+    let explicit = state.itunes_explicit.max(state.media_explicit);
+
+    let itunes_author = if !state.item_itunes_author.is_empty() {
+        state.item_itunes_author.as_str()
+    } else if !state.dc_creator.is_empty() {
+        state.dc_creator.as_str()
+    } else {
+        state.item_atom_author_name.as_str()
     };
 
     // get the first lightning value block, or fallback to first value if no lightning
@@ -268,14 +466,18 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
         .cloned()
         .or_else(|| state.podcast_values.first().cloned());
 
-    let record = SqlInsert {
+    SqlInsert {
         table: "nfitems".to_string(),
         columns: vec![
             "feed_id".to_string(),
             "title".to_string(),
             "link".to_string(),
             "description".to_string(),
+            "description_html".to_string(),
+            "description_text".to_string(),
             "pub_date".to_string(),
+            "pub_date_offset".to_string(),
+            "pub_date_raw".to_string(),
             "itunes_image".to_string(),
             "itunes_author".to_string(),
             "podcast_funding_url".to_string(),
@@ -285,10 +487,15 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
             "enclosure_url".to_string(),
             "enclosure_length".to_string(),
             "enclosure_type".to_string(),
+            "enclosure_type_declared".to_string(),
+            "media_kind".to_string(),
+            "container".to_string(),
             "itunes_episode".to_string(),
             "itunes_episode_type".to_string(),
             "itunes_explicit".to_string(),
+            "explicit_status".to_string(),
             "itunes_duration".to_string(),
+            "itunes_duration_source".to_string(),
             "image".to_string(),
             "itunes_season".to_string(),
             "podcast_transcripts".to_string(),
@@ -296,15 +503,22 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
             "podcast_soundbites".to_string(),
             "podcast_persons".to_string(),
             "podcast_values".to_string(),
+            "podcast_alternate_enclosures".to_string(),
+            "categories".to_string(),
+            "rights".to_string(),
         ],
         values: vec![
             match feed_id { Some(v) => JsonValue::from(v), None => JsonValue::Null },
             JsonValue::from(title),
             JsonValue::from(link),
             JsonValue::from(description),
+            JsonValue::from(description_html),
+            JsonValue::from(description_text),
             JsonValue::from(state.pub_date),
+            JsonValue::from(pub_date_offset),
+            JsonValue::from(pub_date_raw),
             JsonValue::from(state.itunes_image.clone()),
-            JsonValue::from(state.item_itunes_author.clone()),
+            JsonValue::from(itunes_author),
             JsonValue::from(state.podcast_funding_url.clone()),
             JsonValue::from(state.podcast_funding_text.clone()),
             JsonValue::from(guid),
@@ -312,10 +526,16 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
             JsonValue::from(enclosure_url),
             JsonValue::from(enclosure_length),
             JsonValue::from(enclosure_type),
+            JsonValue::from(enclosure_type_declared),
+            JsonValue::from(media_kind),
+            JsonValue::from(container),
             JsonValue::from(itunes_episode),
             JsonValue::from(state.itunes_episode_type.clone()),
-            JsonValue::from(state.itunes_explicit),
+            JsonValue::from(explicit.flag()),
+            // inherited from the channel in write_nfitems when the item doesn't declare one
+            JsonValue::from(explicit.as_str()),
             JsonValue::from(state.itunes_duration),
+            JsonValue::from(state.itunes_duration_source.clone()),
             JsonValue::from(image),
             JsonValue::from(itunes_season),
             serde_json::to_value(&state.podcast_transcripts).unwrap_or(JsonValue::Null),
@@ -323,8 +543,126 @@ pub fn write_nfitems(state: &ParserState, feed_id: Option<i64>) {
             serde_json::to_value(&state.podcast_soundbites).unwrap_or(JsonValue::Null),
             serde_json::to_value(&state.podcast_persons).unwrap_or(JsonValue::Null),
             serde_json::to_value(&podcast_value).unwrap_or(JsonValue::Null),
+            serde_json::to_value(&state.podcast_alternate_enclosures).unwrap_or(JsonValue::Null),
+            JsonValue::from(state.categories.clone()),
+            JsonValue::from(state.rights.trim()),
+        ],
+        feed_id,
+    }
+}
+
+pub fn write_nfitems(state: &mut ParserState, mut item: PendingItem) {
+    // Items that don't declare an explicit status inherit the channel's. The channel's tags
+    // can follow its items, so this waits until the channel has closed.
+    let channel_status = channel_explicit_status(state);
+    if let Some(status) = item.record.value_mut("explicit_status") {
+        if status == ExplicitStatus::Unspecified.as_str() {
+            *status = JsonValue::from(channel_status.as_str());
+        }
+    }
+    // hashed only now so that the inherited status counts as content
+    let content_hash = hashes::item_hash(&item.record.columns, &item.record.values);
+    item.record.columns.push("content_hash".to_string());
+    item.record.values.push(JsonValue::from(content_hash.clone()));
+
+    // In incremental mode items unchanged since the last run aren't written again
+    if let Some(incremental) = state.incremental.as_mut() {
+        if !incremental.track(&item.guid, &content_hash) {
+            return;
+        }
+    }
+    write_record(&item.record, "nfitems");
+    state.items_written += 1;
+}
+
+// Marker for a feed skipped because its ETag or Last-Modified hadn't changed
+pub fn write_unchanged(feed_id: Option<i64>, etag: Option<&str>, last_modified: Option<i64>) {
+    let record = SqlInsert {
+        table: "unchanged".to_string(),
+        columns: vec![
+            "feed_id".to_string(),
+            "etag".to_string(),
+            "last_modified".to_string(),
+        ],
+        values: vec![
+            match feed_id { Some(v) => JsonValue::from(v), None => JsonValue::Null },
+            JsonValue::from(etag),
+            JsonValue::from(last_modified),
+        ],
+        feed_id,
+    };
+    write_record(&record, "unchanged");
+}
+
+// Incremental mode: one record per item that was in the feed last run but isn't any more
+pub fn write_tombstones(state: &ParserState, feed_id: Option<i64>) {
+    let removed = match &state.incremental {
+        Some(incremental) => &incremental.removed,
+        None => return,
+    };
+
+    for (guid, content_hash) in removed {
+        let record = SqlInsert {
+            table: "tombstones".to_string(),
+            columns: vec![
+                "feed_id".to_string(),
+                "guid".to_string(),
+                "content_hash".to_string(),
+            ],
+            values: vec![
+                match feed_id { Some(v) => JsonValue::from(v), None => JsonValue::Null },
+                JsonValue::from(guid.clone()),
+                JsonValue::from(content_hash.clone()),
+            ],
+            feed_id,
+        };
+        write_record(&record, "tombstones");
+    }
+}
+
+// Problems found while parsing (unreadable dates, ...) so publishers can be told what we dropped
+pub fn write_diagnostics(state: &ParserState, feed_id: Option<i64>) {
+    if state.diagnostics.is_empty() {
+        return;
+    }
+
+    let record = SqlInsert {
+        table: "diagnostics".to_string(),
+        columns: vec![
+            "feed_id".to_string(),
+            "diagnostics".to_string(),
+        ],
+        values: vec![
+            match feed_id { Some(v) => JsonValue::from(v), None => JsonValue::Null },
+            serde_json::to_value(&state.diagnostics).unwrap_or(JsonValue::Null),
+        ],
+        feed_id,
+    };
+    write_record(&record, "diagnostics");
+}
+
+// How many items were skipped for the given reason, for the newsfeeds skipped_* columns
+fn skipped_count(state: &ParserState, reason: SkipReason) -> usize {
+    state.skipped_items.iter().filter(|item| item.reason == reason).count()
+}
+
+// The items dropped for not having a usable enclosure, with the reason for each
+pub fn write_skipped_items(state: &ParserState, feed_id: Option<i64>) {
+    if state.skipped_items.is_empty() {
+        return;
+    }
+
+    let record = SqlInsert {
+        table: "skipped_items".to_string(),
+        columns: vec![
+            "feed_id".to_string(),
+            "skipped_items".to_string(),
+        ],
+        values: vec![
+            match feed_id { Some(v) => JsonValue::from(v), None => JsonValue::Null },
+            serde_json::to_value(&state.skipped_items).unwrap_or(JsonValue::Null),
         ],
         feed_id,
     };
-    write_record(&record, "nfitems");
+    write_record(&record, "skipped_items");
 }
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+// Entities every XML parser knows without a declaration
+const XML_ENTITIES: &[&str] = &["amp", "lt", "gt", "quot", "apos"];
+
+// Longest entity name looked for after an ampersand
+const MAX_ENTITY_LENGTH: usize = 32;
+
+/*
+* Fix the mistakes that most often make a feed unparseable, for a second attempt after
+* the first one failed:
+* - anything before the XML declaration or root element (server warnings, stray text)
+* - control characters XML 1.0 doesn't allow
+* - bare ampersands ("Q&A")
+* - entities the parser doesn't know; HTML ones are decoded, anything else is escaped
+* - anything after the root element's closing tag
+* CDATA sections and comments are left as they are. Returns the cleaned text and the
+* names of the fixes that changed something.
+*/
+pub fn clean(text: &str, entities: &HashMap<String, String>) -> (String, Vec<&'static str>) {
+    let mut fixes = Vec::new();
+
+    let start = document_start(text);
+    if !text[..start].trim().is_empty() {
+        fixes.push("leading_garbage");
+    }
+
+    let without_controls: String = text[start..].chars().filter(|c| !is_disallowed(*c)).collect();
+    if without_controls.len() != text.len() - start {
+        fixes.push("control_characters");
+    }
+
+    let mut cleaned = String::with_capacity(without_controls.len());
+    let mut bare_ampersand = false;
+    let mut undefined_entity = false;
+    let mut pos = 0;
+    while let Some(c) = without_controls[pos..].chars().next() {
+        let rest = &without_controls[pos..];
+        if let Some(len) = verbatim_len(rest) {
+            cleaned.push_str(&rest[..len]);
+            pos += len;
+            continue;
+        }
+        if c != '&' {
+            cleaned.push(c);
+            pos += c.len_utf8();
+            continue;
+        }
+
+        match reference(rest) {
+            Some(name) if is_known(name, entities) => cleaned.push('&'),
+            Some(name) if !name.starts_with('#') => {
+                undefined_entity = true;
+                let reference = &rest[..name.len() + 2];
+                let decoded = html_escape::decode_html_entities(reference);
+                if decoded != reference && !decoded.contains(['<', '&']) {
+                    cleaned.push_str(&decoded);
+                    pos += reference.len();
+                    continue;
+                }
+                cleaned.push_str("&amp;");
+            }
+            _ => {
+                bare_ampersand = true;
+                cleaned.push_str("&amp;");
+            }
+        }
+        pos += 1;
+    }
+    if bare_ampersand {
+        fixes.push("bare_ampersand");
+    }
+    if undefined_entity {
+        fixes.push("undefined_entity");
+    }
+
+    let end = document_end(&cleaned);
+    if !cleaned[end..].trim().is_empty() {
+        fixes.push("trailing_garbage");
+    }
+    cleaned.truncate(end);
+
+    (cleaned, fixes)
+}
+
+// Characters XML 1.0 forbids anywhere in a document
+fn is_disallowed(c: char) -> bool {
+    matches!(c, '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}')
+}
+
+// Where the document really begins: the XML declaration, or else the first tag
+fn document_start(text: &str) -> usize {
+    if let Some(pos) = text.find("<?xml") {
+        return pos;
+    }
+    text.char_indices()
+        .find(|(i, c)| {
+            *c == '<'
+                && text[i + 1..]
+                    .chars()
+                    .next()
+                    .is_some_and(|next| next.is_alphabetic() || next == '!')
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+// Just past the root element's closing tag, or the end of the text when it can't be found
+fn document_end(text: &str) -> usize {
+    let root = match root_name(text) {
+        Some(root) => root,
+        None => return text.len(),
+    };
+    let closing = format!("</{}", root);
+    text.rfind(&closing)
+        .and_then(|pos| text[pos..].find('>').map(|end| pos + end + 1))
+        .unwrap_or(text.len())
+}
+
+fn root_name(text: &str) -> Option<&str> {
+    let mut rest = text;
+    loop {
+        rest = &rest[rest.find('<')?..];
+        if rest.starts_with("<?") {
+            rest = &rest[rest.find("?>")? + 2..];
+        } else if rest.starts_with("<!--") {
+            rest = &rest[rest.find("-->")? + 3..];
+        } else if rest.starts_with("<!") {
+            rest = &rest[rest.find('>')? + 1..];
+        } else {
+            let name = &rest[1..];
+            let len = name
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .unwrap_or(name.len());
+            return Some(&name[..len]).filter(|name| !name.is_empty());
+        }
+    }
+}
+
+// Length of a CDATA section or comment starting here, copied without changes
+fn verbatim_len(rest: &str) -> Option<usize> {
+    let (open, close) = if rest.starts_with("<![CDATA[") {
+        ("<![CDATA[", "]]>")
+    } else if rest.starts_with("<!--") {
+        ("<!--", "-->")
+    } else {
+        return None;
+    };
+    Some(
+        rest[open.len()..]
+            .find(close)
+            .map(|end| open.len() + end + close.len())
+            .unwrap_or(rest.len()),
+    )
+}
+
+// The name between "&" and ";" when the ampersand starts a well-formed reference
+fn reference(rest: &str) -> Option<&str> {
+    let body = &rest[1..];
+    let end = body.char_indices().take(MAX_ENTITY_LENGTH).find(|(_, c)| *c == ';')?.0;
+    let name = &body[..end];
+
+    let valid = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+    } else if let Some(digits) = name.strip_prefix('#') {
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    } else {
+        name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    };
+    Some(name).filter(|_| valid)
+}
+
+// A reference the parser will accept as it is
+fn is_known(name: &str, entities: &HashMap<String, String>) -> bool {
+    if let Some(code) = name.strip_prefix('#') {
+        let code = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => code.parse::<u32>().ok(),
+        };
+        return code.and_then(char::from_u32).is_some_and(|c| !is_disallowed(c));
+    }
+    XML_ENTITIES.contains(&name) || entities.contains_key(name)
+}
@@ -0,0 +1,99 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+// What's remembered about a feed between runs
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedIndex {
+    // item guid -> content hash, as of the last run
+    #[serde(default)]
+    pub items: BTreeMap<String, String>,
+    // ETag and Last-Modified from the header of the last complete parse
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<i64>,
+}
+
+impl FeedIndex {
+    // Whether the server's validators say the feed hasn't changed since the last complete parse.
+    // The ETag is the stronger validator, so Last-Modified only counts when there's no ETag to compare.
+    pub fn unchanged(&self, etag: Option<&str>, last_modified: Option<i64>) -> bool {
+        match (self.etag.as_deref(), etag) {
+            (Some(a), Some(b)) => a == b,
+            _ => matches!((self.last_modified, last_modified), (Some(a), Some(b)) if a == b),
+        }
+    }
+}
+
+/*
+* Per-feed state kept across runs, one JSON file per feed_id in a directory.
+* Updates are staged while a feed is processed and only written by commit, so a feed
+* that fails leaves its previous state untouched.
+*/
+pub struct StateStore {
+    dir: PathBuf,
+    staged: Mutex<HashMap<i64, FeedIndex>>,
+}
+
+impl StateStore {
+    pub fn open(dir: impl Into<PathBuf>) -> StateStore {
+        StateStore {
+            dir: dir.into(),
+            staged: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn path(&self, feed_id: i64) -> PathBuf {
+        self.dir.join(format!("{}.json", feed_id))
+    }
+
+    // The feed's state from the last committed run; missing or unreadable files count as no state
+    pub fn load(&self, feed_id: i64) -> Option<FeedIndex> {
+        let contents = fs::read_to_string(self.path(feed_id)).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(index) => Some(index),
+            Err(e) => {
+                eprintln!("Ignoring unreadable state for feed {}: {}", feed_id, e);
+                None
+            }
+        }
+    }
+
+    pub fn stage(&self, feed_id: i64, index: FeedIndex) {
+        if let Ok(mut staged) = self.staged.lock() {
+            staged.insert(feed_id, index);
+        }
+    }
+
+    // Write the staged state for a feed, replacing the old file in one step
+    pub fn commit(&self, feed_id: i64) {
+        let index = match self.staged.lock().ok().and_then(|mut staged| staged.remove(&feed_id)) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if let Err(e) = fs::create_dir_all(&self.dir) {
+            eprintln!("Failed to create state directory '{}': {}", self.dir.display(), e);
+            return;
+        }
+        let path = self.path(feed_id);
+        let tmp_path = path.with_extension("json.tmp");
+        let result = serde_json::to_string(&index)
+            .map_err(|e| e.to_string())
+            .and_then(|serialized| fs::write(&tmp_path, serialized).map_err(|e| e.to_string()))
+            .and_then(|_| fs::rename(&tmp_path, &path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to write state {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn discard(&self, feed_id: i64) {
+        if let Ok(mut staged) = self.staged.lock() {
+            staged.remove(&feed_id);
+        }
+    }
+}
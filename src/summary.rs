@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::models::{FeedOutcome, FeedStatus, SkipReason};
+
+// How many of the slowest feeds the summary lists
+const SLOWEST_FEEDS: usize = 10;
+
+// One feed's result and how long main waited for it
+pub struct FeedRun {
+    pub source_name: String,
+    pub feed_id: Option<i64>,
+    pub outcome: FeedOutcome,
+    pub elapsed: Duration,
+}
+
+/*
+* Totals for a whole run, written as JSON next to the run's output folder for the ops
+* dashboards. Times are in milliseconds; percentiles are over the per-feed times.
+*/
+#[derive(Serialize)]
+pub struct RunSummary {
+    pub started_at: u64,
+    pub runtime_ms: f64,
+    pub feeds: FeedCounts,
+    pub items: ItemCounts,
+    pub bytes_read: u64,
+    pub feed_time_ms: Percentiles,
+    pub slowest_feeds: Vec<SlowFeed>,
+}
+
+#[derive(Serialize, Default)]
+pub struct FeedCounts {
+    pub processed: usize,
+    pub complete: usize,
+    pub recovered: usize,
+    pub partial: usize,
+    pub failed: usize,
+    pub unchanged: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct ItemCounts {
+    pub emitted: usize,
+    pub skipped: usize,
+    pub skipped_by_reason: BTreeMap<SkipReason, usize>,
+}
+
+#[derive(Serialize, Default)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Serialize)]
+pub struct SlowFeed {
+    pub source: String,
+    pub feed_id: Option<i64>,
+    pub status: FeedStatus,
+    pub elapsed_ms: f64,
+}
+
+pub fn build(runs: &[FeedRun], started_at: u64, runtime: Duration) -> RunSummary {
+    let mut feeds = FeedCounts::default();
+    let mut items = ItemCounts::default();
+    let mut bytes_read = 0;
+    for run in runs {
+        feeds.processed += 1;
+        match run.outcome.status {
+            FeedStatus::Complete => feeds.complete += 1,
+            FeedStatus::Recovered => feeds.recovered += 1,
+            FeedStatus::Partial => feeds.partial += 1,
+            FeedStatus::Failed => feeds.failed += 1,
+            FeedStatus::Unchanged => feeds.unchanged += 1,
+        }
+        items.emitted += run.outcome.items_emitted;
+        for (reason, count) in &run.outcome.items_skipped {
+            items.skipped += count;
+            *items.skipped_by_reason.entry(*reason).or_default() += count;
+        }
+        bytes_read += run.outcome.bytes_read;
+    }
+
+    let mut times: Vec<f64> = runs.iter().map(|run| millis(run.elapsed)).collect();
+    times.sort_by(f64::total_cmp);
+    let feed_time_ms = Percentiles {
+        p50: percentile(&times, 50),
+        p90: percentile(&times, 90),
+        p99: percentile(&times, 99),
+        max: times.last().copied().unwrap_or(0.0),
+    };
+
+    let mut slowest: Vec<&FeedRun> = runs.iter().collect();
+    slowest.sort_by_key(|run| std::cmp::Reverse(run.elapsed));
+    let slowest_feeds = slowest
+        .into_iter()
+        .take(SLOWEST_FEEDS)
+        .map(|run| SlowFeed {
+            source: run.source_name.clone(),
+            feed_id: run.feed_id,
+            status: run.outcome.status,
+            elapsed_ms: millis(run.elapsed),
+        })
+        .collect();
+
+    RunSummary {
+        started_at,
+        runtime_ms: millis(runtime),
+        feeds,
+        items,
+        bytes_read,
+        feed_time_ms,
+        slowest_feeds,
+    }
+}
+
+pub fn write(summary: &RunSummary, path: &Path) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(summary)?;
+    fs::write(path, json)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// Nearest-rank percentile of sorted values; 0 when there are none
+fn percentile(sorted: &[f64], percent: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -0,0 +1,20 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    let term = match attributes.iter().find(|a| a.name.local_name == "term") {
+        Some(attr) => attr.value.trim().to_string(),
+        None => return,
+    };
+
+    if term.is_empty() {
+        return;
+    }
+
+    if state.in_item {
+        state.categories.push(term);
+    } else if state.in_channel {
+        state.channel_categories.push(term);
+    }
+}
@@ -0,0 +1,15 @@
+use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+use crate::utils;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_channel && !state.in_item {
+        state.channel_atom_icon.push_str(data.trim());
+    }
+}
+
+pub fn on_end(state: &mut ParserState) {
+    if state.in_channel && !state.in_item {
+        state.channel_atom_icon = utils::resolve_url(xml_base::current(state), &state.channel_atom_icon);
+    }
+}
@@ -0,0 +1,77 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+use crate::tags::{
+    atom_author, atom_email, atom_link, atom_logo, atom_name, content, description, generator, guid,
+    pub_date, title,
+};
+
+pub mod category;
+pub mod icon;
+pub mod rights;
+pub mod updated;
+pub mod xhtml;
+
+/*
+* Atom documents (feed_type 1) are routed here first. Each function returns true
+* when the event was consumed, otherwise the shared RSS dispatch handles it so
+* foreign namespaces (itunes, podcast, media, ...) keep working inside entries.
+*/
+pub fn dispatch_start(current_element: &str, attributes: &[OwnedAttribute], state: &mut ParserState) -> bool {
+    if state.in_atom_xhtml {
+        xhtml::on_xhtml_start(current_element, attributes, state);
+        return true;
+    }
+
+    match current_element {
+        "atom:author" => atom_author::on_start(state),
+        "atom:category" => category::on_start(attributes, state),
+        "atom:content" | "atom:rights" | "atom:subtitle" | "atom:summary" | "atom:title" => {
+            xhtml::on_start(current_element, attributes, state)
+        }
+        "atom:link" => atom_link::on_start(attributes, state),
+        _ => return false,
+    }
+    true
+}
+
+pub fn dispatch_text(current_element: &str, data: &str, state: &mut ParserState) -> bool {
+    if state.in_atom_xhtml {
+        xhtml::on_xhtml_text(data, state);
+        return true;
+    }
+
+    match current_element {
+        "atom:content" => content::on_text(data, state),
+        "atom:email" => atom_email::on_text(data, state),
+        "atom:generator" => generator::on_text(data, state),
+        "atom:icon" => icon::on_text(data, state),
+        "atom:id" => guid::on_text(data, state),
+        "atom:logo" => atom_logo::on_text(data, state),
+        "atom:name" => atom_name::on_text(data, state),
+        "atom:published" => pub_date::on_text(data, state),
+        "atom:rights" => rights::on_text(data, state),
+        "atom:subtitle" | "atom:summary" => description::on_text(data, state),
+        "atom:title" => title::on_text(data, state),
+        "atom:updated" => updated::on_text(data, state),
+        _ => return false,
+    }
+    true
+}
+
+pub fn dispatch_end(current_element: &str, state: &mut ParserState) -> bool {
+    if state.in_atom_xhtml {
+        if let Some((element, markup)) = xhtml::on_xhtml_end(current_element, state) {
+            dispatch_text(&element, &markup, state);
+        }
+        return true;
+    }
+
+    match current_element {
+        "atom:author" => atom_author::on_end(state),
+        "atom:icon" => icon::on_end(state),
+        "atom:logo" => atom_logo::on_end(state),
+        _ => return false,
+    }
+    true
+}
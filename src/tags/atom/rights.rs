@@ -0,0 +1,9 @@
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_item {
+        state.rights.push_str(data);
+    } else if state.in_channel {
+        state.channel_rights.push_str(data);
+    }
+}
@@ -0,0 +1,16 @@
+use crate::parser_state::ParserState;
+use crate::tags::last_build_date;
+use crate::dates;
+
+// <updated> is only a fallback for entries without <published>; at feed level
+// it carries the same meaning as RSS lastBuildDate
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_item {
+        if state.atom_updated == 0 {
+            state.atom_updated_raw = data.trim().to_string();
+            state.atom_updated = dates::timestamp_or_report(data, &state.current_element, &mut state.diagnostics);
+        }
+    } else {
+        last_build_date::on_text(data, state);
+    }
+}
@@ -0,0 +1,86 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+
+/*
+* Atom text constructs (content, summary, subtitle, title, rights) may be
+* type="xhtml", in which case the value is inline markup rather than text.
+* The markup is re-serialized into a buffer and handed to the regular text
+* handler once the construct closes. The wrapping <div> is not part of the value.
+*/
+pub fn on_start(current_element: &str, attributes: &[OwnedAttribute], state: &mut ParserState) {
+    let is_xhtml = attributes
+        .iter()
+        .any(|a| a.name.local_name == "type" && a.value.trim().eq_ignore_ascii_case("xhtml"));
+
+    if is_xhtml {
+        state.in_atom_xhtml = true;
+        state.atom_xhtml_depth = 0;
+        state.atom_xhtml_element = current_element.to_string();
+        state.atom_xhtml_buffer.clear();
+    }
+}
+
+pub fn on_xhtml_start(current_element: &str, attributes: &[OwnedAttribute], state: &mut ParserState) {
+    state.atom_xhtml_depth += 1;
+    if state.atom_xhtml_depth == 1 {
+        return;
+    }
+
+    let tag = local_tag(current_element);
+    state.atom_xhtml_buffer.push('<');
+    state.atom_xhtml_buffer.push_str(tag);
+    for attr in attributes {
+        if attr.name.prefix.as_deref() == Some("xmlns") || attr.name.local_name == "xmlns" {
+            continue;
+        }
+        state.atom_xhtml_buffer.push(' ');
+        state.atom_xhtml_buffer.push_str(&attr.name.local_name);
+        state.atom_xhtml_buffer.push_str("=\"");
+        state.atom_xhtml_buffer.push_str(&escape(&attr.value, true));
+        state.atom_xhtml_buffer.push('"');
+    }
+    state.atom_xhtml_buffer.push('>');
+}
+
+pub fn on_xhtml_text(data: &str, state: &mut ParserState) {
+    if state.atom_xhtml_depth > 0 {
+        state.atom_xhtml_buffer.push_str(&escape(data, false));
+    }
+}
+
+// Returns the construct name and its markup once the construct itself closes
+pub fn on_xhtml_end(current_element: &str, state: &mut ParserState) -> Option<(String, String)> {
+    if state.atom_xhtml_depth == 0 {
+        state.in_atom_xhtml = false;
+        let markup = std::mem::take(&mut state.atom_xhtml_buffer);
+        let element = std::mem::take(&mut state.atom_xhtml_element);
+        return Some((element, markup.trim().to_string()));
+    }
+
+    if state.atom_xhtml_depth > 1 {
+        state.atom_xhtml_buffer.push_str("</");
+        state.atom_xhtml_buffer.push_str(local_tag(current_element));
+        state.atom_xhtml_buffer.push('>');
+    }
+    state.atom_xhtml_depth -= 1;
+    None
+}
+
+fn local_tag(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn escape(s: &str, in_attribute: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if in_attribute => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -1,6 +1,9 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+use crate::urls::{self, UrlKind};
+use crate::utils;
 
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
     let mut rel = String::new();
@@ -18,35 +21,32 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
         }
     }
 
+    let href = utils::resolve_url(xml_base::current(state), href.trim());
+
+    // A missing rel means "alternate" per RFC 4287
     match rel.as_str() {
-        "alternate" => {
+        "alternate" | "" => {
             if state.in_item && state.link.is_empty() {
                 state.link = href.clone();
             } else if state.in_channel && !state.in_item && state.channel_link.is_empty() {
                 state.channel_link = href.clone();
             }
         }
-        "enclosure" => {
-            if state.in_item && state.enclosure_url.is_empty() {
-                state.enclosure_url = href;
-                state.enclosure_length = length;
-                state.enclosure_type = link_type;
-                let url = state.enclosure_url.trim();
-                if url.starts_with("http://") || url.starts_with("https://") {
-                    state.item_has_valid_enclosure = true;
-                }
+        "enclosure" if state.in_item && state.enclosure_url.is_empty() => {
+            state.enclosure_url = href;
+            state.enclosure_length = length;
+            state.enclosure_type = link_type;
+            state.item_enclosure_declared = true;
+            if urls::is_valid(&state.enclosure_url, UrlKind::Media, "atom:link", &mut state.diagnostics) {
+                state.item_has_valid_enclosure = true;
             }
         }
-        "hub" => {
-            if state.in_channel && !state.in_item && state.channel_pubsub_hub_url.is_empty() {
-                state.channel_pubsub_hub_url = href;
-            }
-        },
-        "self" => {
-            if state.in_channel && !state.in_item && state.channel_pubsub_self_url.is_empty() {
-                state.channel_pubsub_self_url = href;
-            }
-        },
+        "hub" if state.in_channel && !state.in_item && state.channel_pubsub_hub_url.is_empty() => {
+            state.channel_pubsub_hub_url = href;
+        }
+        "self" if state.in_channel && !state.in_item && state.channel_pubsub_self_url.is_empty() => {
+            state.channel_pubsub_self_url = href;
+        }
         _ => {}
     }
 }
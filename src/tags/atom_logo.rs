@@ -1,4 +1,6 @@
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+use crate::utils;
 
 /// Handle Atom `<logo>` values as channel-level images.
 pub fn on_text(data: &str, state: &mut ParserState) {
@@ -7,4 +9,9 @@ pub fn on_text(data: &str, state: &mut ParserState) {
     }
 }
 
-
+/// Resolve a relative logo against `xml:base` or the feed URL.
+pub fn on_end(state: &mut ParserState) {
+    if state.in_channel && !state.in_item {
+        state.channel_image = utils::resolve_url(xml_base::current(state), state.channel_image.trim());
+    }
+}
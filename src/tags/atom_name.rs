@@ -1,9 +1,13 @@
 use crate::parser_state::ParserState;
 
 pub fn on_text(data: &str, state: &mut ParserState) {
-    if state.in_channel_atom_author {
+    if state.in_item_atom_author {
+        // first entry author wins
+        if state.item_atom_author_name.is_empty() {
+            state.item_atom_author_name.push_str(data);
+        }
+    } else if state.in_channel_atom_author {
         state.channel_atom_author_name.clear();
         state.channel_atom_author_name.push_str(data);
     }
 }
-
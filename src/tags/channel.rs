@@ -1,7 +1,11 @@
+use crate::duplicates::{self, ItemSet};
+use crate::models::{ExplicitStatus, FeedStatus};
 use crate::outputs;
 use crate::parser_state::ParserState;
+use crate::tags::{item, xml_base};
 
 pub fn on_start(state: &mut ParserState) {
+    state.channel_opened = true;
     state.in_channel = true;
     state.in_channel_atom_author = false;
     state.in_channel_image = false;
@@ -11,12 +15,24 @@ pub fn on_start(state: &mut ParserState) {
     state.in_channel_podcast_funding = false;
     state.in_channel_podcast_locked = false;
     state.in_channel_podcast_value = false;
+    state.in_channel_textinput = false;
 
     state.channel_atom_author_email.clear();
     state.channel_atom_author_name.clear();
+    state.channel_atom_icon.clear();
+    state.channel_categories.clear();
+    state.channel_dc_creator.clear();
+    state.channel_dc_date = 0;
+    state.channel_dc_date_raw.clear();
+    state.channel_dc_language.clear();
     state.channel_description.clear();
-    state.channel_explicit = 0;
+    state.channel_explicit = ExplicitStatus::Unspecified;
     state.channel_generator.clear();
+    state.channel_googleplay_author.clear();
+    state.channel_googleplay_block = 0;
+    state.channel_googleplay_description.clear();
+    state.channel_googleplay_explicit = ExplicitStatus::Unspecified;
+    state.channel_googleplay_image.clear();
     state.channel_image.clear();
     state.channel_itunes_author.clear();
     state.channel_itunes_categories.clear();
@@ -31,29 +47,64 @@ pub fn on_start(state: &mut ParserState) {
     state.channel_itunes_type.clear();
     state.channel_language.clear();
     state.channel_last_build_date = 0;
+    state.channel_last_build_date_raw.clear();
     state.channel_link.clear();
+    state.channel_media_description.clear();
+    state.channel_media_explicit = ExplicitStatus::Unspecified;
+    state.channel_media_thumbnail.clear();
     state.channel_podcast_funding_text.clear();
     state.channel_podcast_funding_url.clear();
     state.channel_podcast_guid.clear();
     state.channel_podcast_locked = 0;
+    state.channel_podcast_medium.clear();
     state.channel_podcast_owner.clear();
     state.channel_podcast_values.clear();
     state.channel_pub_date = 0;
+    state.channel_pub_date_raw.clear();
     state.channel_pubsub_hub_url.clear();
     state.channel_pubsub_self_url.clear();
+    state.channel_rights.clear();
+    state.channel_spotify_country_of_origin.clear();
+    state.channel_spotify_limit.clear();
     state.channel_title.clear();
     state.channel_value_model_method.clear();
     state.channel_value_model_suggested.clear();
     state.channel_value_model_type.clear();
     state.channel_value_recipients.clear();
 
+    state.duplicate_items = 0;
     state.item_count = 0;
+    state.item_media_kinds.clear();
     state.item_pubdates.clear();
+    state.items_written = 0;
+    state.pending_items = ItemSet::default();
+    state.skipped_items.clear();
 }
 
 pub fn on_end(feed_id: Option<i64>, state: &mut ParserState) {
     if state.in_channel {
+        // Text-valued URLs are resolved against the channel's xml:base or the feed URL
+        state.channel_link = xml_base::resolve(state, &state.channel_link);
+        state.channel_image = xml_base::resolve(state, &state.channel_image);
+        state.channel_itunes_image = xml_base::resolve(state, &state.channel_itunes_image);
+        state.channel_itunes_new_feed_url = xml_base::resolve(state, &state.channel_itunes_new_feed_url);
+
+        for pending in duplicates::resolve(state) {
+            item::write(state, pending);
+        }
+
+        // a partial parse didn't see every item, so nothing can be called removed
+        if state.parse_status != FeedStatus::Partial {
+            if let Some(incremental) = state.incremental.as_mut() {
+                incremental.finish();
+            }
+        }
+
         outputs::write_newsfeeds(state, feed_id);
+        outputs::write_tombstones(state, feed_id);
+        outputs::write_diagnostics(state, feed_id);
+        outputs::write_skipped_items(state, feed_id);
         state.in_channel = false;
+        state.newsfeeds_written = true;
     }
 }
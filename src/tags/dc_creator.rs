@@ -0,0 +1,11 @@
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_item {
+        if state.dc_creator.is_empty() {
+            state.dc_creator.push_str(data);
+        }
+    } else if state.in_channel && state.channel_dc_creator.is_empty() {
+        state.channel_dc_creator.push_str(data);
+    }
+}
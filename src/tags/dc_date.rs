@@ -0,0 +1,14 @@
+use crate::parser_state::ParserState;
+use crate::dates;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_item {
+        if state.dc_date == 0 {
+            state.dc_date_raw = data.trim().to_string();
+            state.dc_date = dates::timestamp_or_report(data, &state.current_element, &mut state.diagnostics);
+        }
+    } else if state.in_channel && state.channel_dc_date == 0 {
+        state.channel_dc_date_raw = data.trim().to_string();
+        state.channel_dc_date = dates::timestamp_or_report(data, &state.current_element, &mut state.diagnostics);
+    }
+}
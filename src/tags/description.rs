@@ -5,9 +5,7 @@ pub fn on_text(data: &str, state: &mut ParserState) {
         if state.description.is_empty() {
             state.description.push_str(data);
         }
-    } else if state.in_channel && !state.in_channel_image {
-        if state.channel_description.is_empty() {
-            state.channel_description.push_str(data);
-        }
+    } else if state.in_channel && !state.in_channel_image && !state.in_channel_textinput && state.channel_description.is_empty() {
+        state.channel_description.push_str(data);
     }
 }
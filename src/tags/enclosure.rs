@@ -1,12 +1,16 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+use crate::urls::{self, UrlKind};
 
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
     if !state.in_item {
         return;
     }
 
+    state.item_enclosure_declared = true;
+
     // Only use the first enclosure (skip if already set)
     if !state.enclosure_url.is_empty() {
         return;
@@ -14,16 +18,15 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
 
     for attr in attributes {
         match attr.name.local_name.as_str() {
-            "url" => state.enclosure_url = attr.value.clone(),
+            "url" => state.enclosure_url = xml_base::resolve(state, &attr.value),
             "length" => state.enclosure_length = attr.value.clone(),
             "type" => state.enclosure_type = attr.value.clone(),
             _ => {}
         }
     }
 
-    // Treat only sane URLs (http/https) as valid enclosures
-    let url = state.enclosure_url.trim();
-    if !url.is_empty() && (url.starts_with("http://") || url.starts_with("https://")) {
+    // Treat only sane URLs (http/https with a host) as valid enclosures
+    if urls::is_valid(&state.enclosure_url, UrlKind::Media, "enclosure", &mut state.diagnostics) {
         state.item_has_valid_enclosure = true;
     }
 }
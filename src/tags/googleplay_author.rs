@@ -0,0 +1,7 @@
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_channel && !state.in_item && state.channel_googleplay_author.is_empty() {
+        state.channel_googleplay_author.push_str(data);
+    }
+}
@@ -0,0 +1,8 @@
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_channel && !state.in_item {
+        let val = data.trim().to_ascii_lowercase();
+        state.channel_googleplay_block = if matches!(val.as_str(), "yes" | "true") { 1 } else { 0 };
+    }
+}
@@ -0,0 +1,8 @@
+use crate::models::ExplicitStatus;
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_channel && !state.in_item {
+        state.channel_googleplay_explicit = ExplicitStatus::from_value(data);
+    }
+}
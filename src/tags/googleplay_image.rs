@@ -0,0 +1,14 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    if !state.in_channel || state.in_item {
+        return;
+    }
+
+    if let Some(attr) = attributes.iter().find(|a| a.name.local_name == "href") {
+        state.channel_googleplay_image = xml_base::resolve(state, &attr.value);
+    }
+}
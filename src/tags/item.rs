@@ -1,16 +1,26 @@
-use crate::{outputs, parser_state::ParserState, utils};
+use crate::{durations, mime, outputs, parser_state::ParserState};
+use crate::duplicates::PendingItem;
+use crate::models::{ExplicitStatus, SkipReason, SkippedItem};
+use crate::tags::xml_base;
+use crate::urls::{self, UrlKind, UrlRejection};
 
 pub fn on_start(state: &mut ParserState) {
     state.in_item = true;
+    state.in_item_atom_author = false;
     state.in_item_image = false;
     state.in_itunes_author = false;
+    state.in_media_group = false;
     state.in_podcast_alternate_enclosure = false;
     state.in_podcast_funding = false;
     state.in_podcast_person = false;
     state.in_podcast_soundbite = false;
     state.in_podcast_value = false;
+    state.item_enclosure_declared = false;
     state.item_has_valid_enclosure = false;
 
+    state.atom_updated = 0;
+    state.atom_updated_raw.clear();
+    state.categories.clear();
     state.content.clear();
     state.content_encoded.clear();
     state.current_person_group.clear();
@@ -21,22 +31,37 @@ pub fn on_start(state: &mut ParserState) {
     state.current_soundbite_duration.clear();
     state.current_soundbite_start.clear();
     state.current_soundbite_title.clear();
+    state.dc_creator.clear();
+    state.dc_date = 0;
+    state.dc_date_raw.clear();
     state.description.clear();
     state.enclosure_length.clear();
     state.enclosure_type.clear();
+    state.enclosure_type_declared.clear();
     state.enclosure_url.clear();
     state.guid.clear();
+    state.item_atom_author_name.clear();
     state.item_image.clear();
     state.item_itunes_author.clear();
     state.itunes_duration = 0;
+    state.itunes_duration_source.clear();
     state.itunes_episode.clear();
     state.itunes_episode_type.clear();
-    state.itunes_explicit = 0;
+    state.itunes_explicit = ExplicitStatus::Unspecified;
     state.itunes_image.clear();
     state.itunes_season.clear();
     state.itunes_summary.clear();
     state.itunes_title.clear();
     state.link.clear();
+    state.media_content_duration.clear();
+    state.media_content_is_default = false;
+    state.media_content_length.clear();
+    state.media_content_type.clear();
+    state.media_content_url.clear();
+    state.media_description.clear();
+    state.media_explicit = ExplicitStatus::Unspecified;
+    state.media_thumbnail.clear();
+    state.podcast_alternate_enclosures.clear();
     state.podcast_chapters.clear();
     state.podcast_funding_text.clear();
     state.podcast_funding_url.clear();
@@ -44,6 +69,8 @@ pub fn on_start(state: &mut ParserState) {
     state.podcast_transcripts.clear();
     state.podcast_values.clear();
     state.pub_date = 0;
+    state.pub_date_raw.clear();
+    state.rights.clear();
     state.title.clear();
     state.value_model_method.clear();
     state.value_model_suggested.clear();
@@ -56,23 +83,95 @@ pub fn on_end(feed_id: Option<i64>, state: &mut ParserState) {
         return;
     }
 
+    // Fall back to Media RSS content when no enclosure was found
+    if !state.item_has_valid_enclosure && urls::is_valid(&state.media_content_url, UrlKind::Media, "media:content", &mut state.diagnostics) {
+        state.enclosure_url = state.media_content_url.clone();
+        state.enclosure_length = state.media_content_length.clone();
+        state.enclosure_type = state.media_content_type.clone();
+        if state.itunes_duration_source.is_empty() {
+            if let Some(seconds) = durations::parse(&state.media_content_duration) {
+                state.itunes_duration = i32::try_from(seconds).unwrap_or(i32::MAX);
+                state.itunes_duration_source = "declared".to_string();
+            }
+        }
+        state.item_has_valid_enclosure = true;
+    }
+
     if !state.item_has_valid_enclosure {
+        state.skipped_items.push(SkippedItem {
+            title: state.title.trim().to_string(),
+            guid: state.guid.trim().to_string(),
+            reason: skip_reason(state),
+        });
         state.in_item = false;
         return;
     }
 
+    // Attribute URLs are resolved as they are captured; text URLs once the item closes
+    state.link = xml_base::resolve(state, &state.link);
+    state.item_image = xml_base::resolve(state, &state.item_image);
+    state.itunes_image = xml_base::resolve(state, &state.itunes_image);
+
     if state.guid.trim().is_empty() {
         state.guid = state.enclosure_url.clone();
     }
 
-    if state.enclosure_type.trim().is_empty() {
-        state.enclosure_type = utils::guess_enclosure_type(&state.enclosure_url);
+    // The raw string follows whichever source supplied the date; an unreadable pubDate is kept when none did
+    if state.pub_date == 0 {
+        if state.dc_date != 0 {
+            state.pub_date = state.dc_date;
+            state.pub_date_raw = state.dc_date_raw.clone();
+        } else if state.atom_updated != 0 {
+            state.pub_date = state.atom_updated;
+            state.pub_date_raw = state.atom_updated_raw.clone();
+        }
     }
 
-    outputs::write_nfitems(state, feed_id);
+    state.enclosure_type_declared = state.enclosure_type.trim().to_string();
+    state.enclosure_type = mime::normalize(&state.enclosure_type, &state.enclosure_url);
 
-    state.item_pubdates.push(state.pub_date);
-    state.item_count += 1;
+    if state.itunes_duration_source.is_empty() {
+        match durations::estimate(&state.enclosure_length, &state.enclosure_type) {
+            Some(seconds) => {
+                state.itunes_duration = i32::try_from(seconds).unwrap_or(i32::MAX);
+                state.itunes_duration_source = "estimated".to_string();
+            }
+            None => state.itunes_duration_source = "missing".to_string(),
+        }
+    }
+
+    let record = outputs::nfitems_record(state, feed_id);
+    let item = PendingItem::new(state, record);
+    if state.in_channel {
+        // Written when the channel closes, once duplicates are known
+        state.pending_items.add(item);
+    } else {
+        // No channel left to close, like an item after </channel>
+        write(state, item);
+    }
 
     state.in_item = false;
-}
\ No newline at end of file
+}
+
+// Write a finished item and count it toward the channel's totals
+pub fn write(state: &mut ParserState, item: PendingItem) {
+    state.item_media_kinds.push(item.media_kind);
+    state.item_pubdates.push(item.pub_date);
+    state.item_count += 1;
+    outputs::write_nfitems(state, item);
+}
+
+// Why an item without a valid enclosure is being dropped
+fn skip_reason(state: &ParserState) -> SkipReason {
+    let url = if state.enclosure_url.trim().is_empty() {
+        &state.media_content_url
+    } else {
+        &state.enclosure_url
+    };
+    match urls::normalize(url, UrlKind::Media) {
+        Err(UrlRejection::Empty) if state.item_enclosure_declared => SkipReason::EmptyUrl,
+        Err(UrlRejection::Empty) => SkipReason::NoEnclosure,
+        Err(UrlRejection::NonHttpScheme(_)) => SkipReason::NonHttpEnclosure,
+        _ => SkipReason::InvalidUrl,
+    }
+}
@@ -5,9 +5,7 @@ pub fn on_text(data: &str, state: &mut ParserState) {
         if state.item_itunes_author.is_empty() {
             state.item_itunes_author.push_str(data);
         }
-    } else if state.in_channel {
-        if state.channel_itunes_author.is_empty() {
-            state.channel_itunes_author.push_str(data);
-        }
+    } else if state.in_channel && state.channel_itunes_author.is_empty() {
+        state.channel_itunes_author.push_str(data);
     }
 }
\ No newline at end of file
@@ -1,14 +1,29 @@
+use crate::durations;
+use crate::models::Diagnostic;
 use crate::parser_state::ParserState;
-use crate::utils;
 
 pub fn on_start(state: &mut ParserState) {
     if state.in_item && !state.in_podcast_alternate_enclosure {
         state.itunes_duration = 0;
+        state.itunes_duration_source.clear();
     }
 }
 
 pub fn on_text(data: &str, state: &mut ParserState) {
     if state.in_item && !state.in_podcast_alternate_enclosure {
-        state.itunes_duration = utils::time_to_seconds(data);
+        match durations::parse(data) {
+            Some(seconds) => {
+                state.itunes_duration = i32::try_from(seconds).unwrap_or(i32::MAX);
+                state.itunes_duration_source = "declared".to_string();
+            }
+            None if !data.trim().is_empty() => {
+                state.diagnostics.push(Diagnostic {
+                    code: "unparseable_duration".to_string(),
+                    element: state.current_element.clone(),
+                    value: data.trim().to_string(),
+                });
+            }
+            None => {}
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
 
 // Handle both channel- and item-level itunes:image elements, supporting the
 // common href/url attribute as well as text content fallback.
@@ -10,9 +11,9 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
         key == "href" || key == "url"
     }) {
         if state.in_item {
-            state.itunes_image = attr.value.clone();
+            state.itunes_image = xml_base::resolve(state, &attr.value);
         } else if state.in_channel {
-            state.channel_itunes_image = attr.value.clone();
+            state.channel_itunes_image = xml_base::resolve(state, &attr.value);
         }
     }
 }
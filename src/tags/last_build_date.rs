@@ -1,9 +1,10 @@
 use crate::parser_state::ParserState;
-use crate::utils;
+use crate::dates;
 
 pub fn on_text(data: &str, state: &mut ParserState) {
     if state.in_channel && !state.in_item && state.channel_last_build_date == 0 {
-        state.channel_last_build_date = utils::pub_date_to_timestamp(data);
+        state.channel_last_build_date_raw = data.trim().to_string();
+        state.channel_last_build_date = dates::timestamp_or_report(data, &state.current_element, &mut state.diagnostics);
     }
 }
 
@@ -1,6 +1,7 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
 
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
     let mut rel = String::new();
@@ -9,7 +10,7 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
     for attr in attributes {
         match attr.name.local_name.as_str() {
             "rel" => rel = attr.value.clone(),
-            "href" => href = attr.value.clone(),
+            "href" => href = xml_base::resolve(state, &attr.value),
             _ => {}
         }
     }
@@ -47,9 +48,7 @@ pub fn on_text(data: &str, state: &mut ParserState) {
         if state.link.is_empty() {
             state.link.push_str(data);
         }
-    } else if state.in_channel && !state.in_channel_image {
-        if state.channel_link.is_empty() {
-            state.channel_link.push_str(data);
-        }
+    } else if state.in_channel && !state.in_channel_image && !state.in_channel_textinput && state.channel_link.is_empty() {
+        state.channel_link.push_str(data);
     }
 }
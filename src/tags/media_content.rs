@@ -0,0 +1,59 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::mime;
+use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+
+// Capture Media RSS content as an enclosure candidate. The candidate is only
+// promoted to the item enclosure in item::on_end when no regular enclosure exists.
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    if !state.in_item {
+        return;
+    }
+
+    let mut url = String::new();
+    let mut file_size = String::new();
+    let mut content_type = String::new();
+    let mut medium = String::new();
+    let mut duration = String::new();
+    let mut is_default = false;
+
+    for attr in attributes {
+        match attr.name.local_name.as_str() {
+            "url" => url = xml_base::resolve(state, &attr.value),
+            "fileSize" => file_size = attr.value.clone(),
+            "type" => content_type = attr.value.clone(),
+            "medium" => medium = attr.value.to_ascii_lowercase(),
+            "duration" => duration = attr.value.clone(),
+            "isDefault" => is_default = attr.value.eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+    }
+
+    if url.is_empty() || !is_playable(&medium, &content_type, &url) {
+        return;
+    }
+
+    // Outside of a group the first media:content wins; inside a media:group the
+    // isDefault alternative replaces any earlier candidate
+    let replace_candidate = state.media_content_url.is_empty()
+        || (state.in_media_group && is_default && !state.media_content_is_default);
+
+    if replace_candidate {
+        state.media_content_url = url;
+        state.media_content_length = file_size;
+        state.media_content_type = content_type;
+        state.media_content_duration = duration;
+        state.media_content_is_default = is_default;
+    }
+}
+
+// Blog feeds attach images through media:content, so only audio and video count
+fn is_playable(medium: &str, content_type: &str, url: &str) -> bool {
+    if !medium.is_empty() {
+        return medium == "audio" || medium == "video";
+    }
+
+    // a missing or generic type falls back to the URL's extension inside normalize
+    mime::is_playable(&mime::normalize(content_type, url))
+}
@@ -0,0 +1,11 @@
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_item {
+        if state.media_description.is_empty() {
+            state.media_description.push_str(data);
+        }
+    } else if state.in_channel && state.channel_media_description.is_empty() {
+        state.channel_media_description.push_str(data);
+    }
+}
@@ -0,0 +1,11 @@
+use crate::parser_state::ParserState;
+
+pub fn on_start(state: &mut ParserState) {
+    if state.in_item {
+        state.in_media_group = true;
+    }
+}
+
+pub fn on_end(state: &mut ParserState) {
+    state.in_media_group = false;
+}
@@ -0,0 +1,32 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::models::ExplicitStatus;
+use crate::parser_state::ParserState;
+
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    // urn:simple is the default scheme when none is given
+    state.current_media_rating_scheme = attributes
+        .iter()
+        .find(|a| a.name.local_name == "scheme")
+        .map(|a| a.value.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "urn:simple".to_string());
+}
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    let val = data.trim().to_ascii_lowercase();
+    let status = match (state.current_media_rating_scheme.as_str(), val.as_str()) {
+        ("urn:simple", "adult") => ExplicitStatus::Explicit,
+        ("urn:simple", "nonadult") => ExplicitStatus::Clean,
+        ("urn:mpaa", "r" | "nc-17" | "x") => ExplicitStatus::Explicit,
+        ("urn:mpaa", "g" | "pg" | "pg-13") => ExplicitStatus::Clean,
+        ("urn:v-chip", "tv-ma") => ExplicitStatus::Explicit,
+        ("urn:v-chip", "tv-y" | "tv-y7" | "tv-y7-fv" | "tv-g" | "tv-pg" | "tv-14") => ExplicitStatus::Clean,
+        _ => ExplicitStatus::Unspecified,
+    };
+
+    if state.in_item {
+        state.media_explicit = status;
+    } else if state.in_channel {
+        state.channel_media_explicit = status;
+    }
+}
@@ -0,0 +1,17 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    if let Some(attr) = attributes.iter().find(|a| a.name.local_name == "url") {
+        // Only the first thumbnail is kept, groups often list several sizes
+        if state.in_item {
+            if state.media_thumbnail.is_empty() {
+                state.media_thumbnail = xml_base::resolve(state, &attr.value);
+            }
+        } else if state.in_channel && state.channel_media_thumbnail.is_empty() {
+            state.channel_media_thumbnail = xml_base::resolve(state, &attr.value);
+        }
+    }
+}
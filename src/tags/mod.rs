@@ -2,15 +2,24 @@ use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
 
+pub mod atom;
 pub mod atom_author;
 pub mod atom_email;
 pub mod atom_link;
 pub mod atom_name;
 pub mod channel;
 pub mod content_encoded;
+pub mod dc_creator;
+pub mod dc_date;
+pub mod dc_language;
 pub mod description;
 pub mod enclosure;
 pub mod generator;
+pub mod googleplay_author;
+pub mod googleplay_block;
+pub mod googleplay_description;
+pub mod googleplay_explicit;
+pub mod googleplay_image;
 pub mod guid;
 pub mod image;
 pub mod item;
@@ -33,21 +42,38 @@ pub mod itunes_type;
 pub mod language;
 pub mod last_build_date;
 pub mod link;
+pub mod media_content;
+pub mod media_description;
+pub mod media_group;
+pub mod media_rating;
+pub mod media_thumbnail;
 pub mod podcast_alternate_enclosure;
 pub mod podcast_chapters;
 pub mod podcast_funding;
 pub mod podcast_guid;
+pub mod podcast_medium;
 pub mod podcast_locked;
 pub mod podcast_person;
 pub mod podcast_soundbite;
 pub mod podcast_transcript;
 pub mod podcast_value;
 pub mod pub_date;
+pub mod rdf;
+pub mod spotify_country_of_origin;
+pub mod spotify_limit;
 pub mod content;
+pub mod textinput;
 pub mod title;
 pub mod url;
+pub mod xml_base;
 
 pub fn dispatch_start(current_element: &str, attributes: &[OwnedAttribute], state: &mut ParserState) {
+    xml_base::on_start(attributes, state);
+
+    if state.feed_type == 1 && atom::dispatch_start(current_element, attributes, state) {
+        return;
+    }
+
     match current_element {
         "atom:author" | "author" => atom_author::on_start(state),
         "atom:feed" => {
@@ -55,11 +81,13 @@ pub fn dispatch_start(current_element: &str, attributes: &[OwnedAttribute], stat
             channel::on_start(state);
         }
         "atom:link" => atom_link::on_start(attributes, state),
-        "channel" => {
+        // In RSS 1.0 the channel scope was already opened by <rdf:RDF>
+        "channel" if state.feed_type != 2 => {
             state.feed_type = 0; // rss
             channel::on_start(state);
         }
         "enclosure" => enclosure::on_start(attributes, state),
+        "googleplay:image" => googleplay_image::on_start(attributes, state),
         "image" => image::on_start(state),
         "item" | "atom:entry" => item::on_start(state),
         "itunes:category" => itunes_category::on_start(attributes, state),
@@ -67,20 +95,32 @@ pub fn dispatch_start(current_element: &str, attributes: &[OwnedAttribute], stat
         "itunes:image" => itunes_image::on_start(attributes, state),
         "itunes:owner" => itunes_owner::on_start(state),
         "link" => link::on_start( attributes, state),
-        "podcast:alternateEnclosure" => podcast_alternate_enclosure::on_start(state),
+        "media:content" => media_content::on_start(attributes, state),
+        "media:group" => media_group::on_start(state),
+        "media:rating" => media_rating::on_start(attributes, state),
+        "media:thumbnail" => media_thumbnail::on_start(attributes, state),
+        "podcast:alternateEnclosure" => podcast_alternate_enclosure::on_start(attributes, state),
         "podcast:chapters" => podcast_chapters::on_start(attributes, state),
         "podcast:funding" => podcast_funding::on_start(attributes, state),
         "podcast:locked" => podcast_locked::on_start( attributes, state),
         "podcast:person" => podcast_person::on_start(attributes, state),
         "podcast:soundbite" => podcast_soundbite::on_start(attributes, state),
+        "podcast:source" => podcast_alternate_enclosure::on_source(attributes, state),
         "podcast:transcript" => podcast_transcript::on_start(attributes, state),
         "podcast:value" => podcast_value::on_start(attributes, state),
         "podcast:valueRecipient" => podcast_value::on_value_recipient(attributes, state),
+        "rdf:RDF" => rdf::on_start(state),
+        "spotify:limit" => spotify_limit::on_start(attributes, state),
+        "textInput" | "textinput" => textinput::on_start(state),
         _ => {}
     }
 }
 
 pub fn dispatch_text(current_element: &str, data: &str, state: &mut ParserState) {
+    if state.feed_type == 1 && atom::dispatch_text(current_element, data, state) {
+        return;
+    }
+
     match current_element {
         "atom:email" | "email" => atom_email::on_text(data, state),
         "atom:logo" => atom_logo::on_text(data, state),
@@ -88,8 +128,15 @@ pub fn dispatch_text(current_element: &str, data: &str, state: &mut ParserState)
         "atom:summary" => description::on_text(data, state),
         "content" => content::on_text(data, state),
         "content:encoded" => content_encoded::on_text(data, state),
+        "dc:creator" => dc_creator::on_text(data, state),
+        "dc:date" => dc_date::on_text(data, state),
+        "dc:language" => dc_language::on_text(data, state),
         "description" | "atom:subtitle" => description::on_text(data, state),
         "generator" => generator::on_text(data, state),
+        "googleplay:author" => googleplay_author::on_text(data, state),
+        "googleplay:block" => googleplay_block::on_text(data, state),
+        "googleplay:description" => googleplay_description::on_text(data, state),
+        "googleplay:explicit" => googleplay_explicit::on_text(data, state),
         "guid" => guid::on_text(data, state),
         "id" => guid::on_text(data, state),
         "itunes:author" => itunes_author::on_text(data, state),
@@ -108,13 +155,17 @@ pub fn dispatch_text(current_element: &str, data: &str, state: &mut ParserState)
         "language" => language::on_text(data, state),
         "lastBuildDate" => last_build_date::on_text(data, state),
         "link" => link::on_text(data, state),
+        "media:description" => media_description::on_text(data, state),
+        "media:rating" => media_rating::on_text(data, state),
         "podcast:funding" => podcast_funding::on_text(data, state),
         "podcast:guid" => podcast_guid::on_text(data, state),
+        "podcast:medium" => podcast_medium::on_text(data, state),
         "podcast:locked" => podcast_locked::on_text(data, state),
         "podcast:person" => podcast_person::on_text(data, state),
         "podcast:soundbite" => podcast_soundbite::on_text(data, state),
         "pubDate" => pub_date::on_text(data, state),
         "published" | "atom:updated" => pub_date::on_text(data, state),
+        "spotify:countryOfOrigin" => spotify_country_of_origin::on_text(data, state),
         "subtitle" => description::on_text(data, state),
         "title" => title::on_text(data, state),
         "url" => url::on_text(data, state),
@@ -123,18 +174,30 @@ pub fn dispatch_text(current_element: &str, data: &str, state: &mut ParserState)
 }
 
 pub fn dispatch_end(current_element: &str, feed_id: Option<i64>, state: &mut ParserState) {
+    if state.feed_type != 1 || !atom::dispatch_end(current_element, state) {
+        dispatch_end_element(current_element, feed_id, state);
+    }
+
+    xml_base::on_end(state);
+}
+
+fn dispatch_end_element(current_element: &str, feed_id: Option<i64>, state: &mut ParserState) {
     match current_element {
         "atom:author" | "author" => atom_author::on_end(state),
-        "channel" | "atom:feed" => channel::on_end(feed_id, state),
+        "atom:feed" => channel::on_end(feed_id, state),
+        "channel" if state.feed_type != 2 => channel::on_end(feed_id, state),
         "image" => image::on_end(state),
         "item" | "atom:entry" => item::on_end(feed_id, state),
         "itunes:owner" => itunes_owner::on_end(state),
+        "media:group" => media_group::on_end(state),
         "podcast:alternateEnclosure" => podcast_alternate_enclosure::on_end(state),
         "podcast:funding" => podcast_funding::on_end(state),
         "podcast:locked" | "locked" => podcast_locked::on_end(state),
         "podcast:person" => podcast_person::on_end(feed_id, state),
         "podcast:soundbite" => podcast_soundbite::on_end(feed_id, state),
         "podcast:value" => podcast_value::on_end(feed_id, state),
+        "rdf:RDF" => rdf::on_end(feed_id, state),
+        "textInput" | "textinput" => textinput::on_end(state),
         _ => {}
     }
 }
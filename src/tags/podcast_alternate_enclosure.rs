@@ -1,11 +1,58 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::languages;
+use crate::models::PodcastAlternateEnclosure;
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
+use crate::urls::{self, UrlKind};
+use crate::utils;
+
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    if !state.in_item {
+        return;
+    }
+
+    state.in_podcast_alternate_enclosure = true;
+
+    // cut to the same sizes as the matching nfitems columns
+    let mut enclosure = PodcastAlternateEnclosure::default();
+    for attr in attributes {
+        let value = attr.value.trim();
+        match attr.name.local_name.as_str() {
+            "type" => enclosure.r#type = utils::truncate_string(value, 128),
+            "length" => enclosure.length = utils::truncate_string(value, 32),
+            "bitrate" => enclosure.bitrate = utils::truncate_string(value, 32),
+            "height" => enclosure.height = utils::truncate_string(value, 32),
+            "lang" => enclosure.lang = languages::column_value(value),
+            "title" => enclosure.title = utils::truncate_string(value, 128),
+            "rel" => enclosure.rel = utils::truncate_string(value, 32),
+            "default" => enclosure.default = attr.value.trim().eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+    }
+    state.podcast_alternate_enclosures.push(enclosure);
+}
+
+pub fn on_source(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    if !state.in_item || !state.in_podcast_alternate_enclosure {
+        return;
+    }
 
-pub fn on_start(state: &mut ParserState) {
-    if state.in_item {
-        state.in_podcast_alternate_enclosure = true;
+    // sources are media URLs like the enclosure: http(s) only, rejected ones are dropped
+    let uri = match attributes.iter().find(|attr| attr.name.local_name == "uri") {
+        Some(attr) => xml_base::resolve(state, &attr.value),
+        None => return,
+    };
+    let uri = urls::sanitize(&uri, UrlKind::Media, "podcast:source", &mut state.diagnostics);
+    if uri.is_empty() {
+        return;
+    }
+
+    if let Some(enclosure) = state.podcast_alternate_enclosures.last_mut() {
+        enclosure.sources.push(uri);
     }
 }
 
 pub fn on_end(state: &mut ParserState) {
     state.in_podcast_alternate_enclosure = false;
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
 use crate::models::PodcastChapter;
 
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
@@ -14,7 +15,7 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
 
     for attr in attributes {
         match attr.name.local_name.as_str() {
-            "url" => chapter_url = attr.value.clone(),
+            "url" => chapter_url = xml_base::resolve(state, &attr.value),
             "type" => chapter_type = attr.value.clone(),
             _ => {}
         }
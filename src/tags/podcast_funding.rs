@@ -1,18 +1,19 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
 
 // Detect podcast:funding start; set flag and capture optional url attribute
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
     if state.in_item {
         state.in_podcast_funding = true;
         if let Some(attr) = attributes.iter().find(|a| a.name.local_name == "url") {
-            state.podcast_funding_url = attr.value.clone();
+            state.podcast_funding_url = xml_base::resolve(state, &attr.value);
         }
     } else if state.in_channel {
         state.in_channel_podcast_funding = true;
         if let Some(attr) = attributes.iter().find(|a| a.name.local_name == "url") {
-            state.channel_podcast_funding_url = attr.value.clone();
+            state.channel_podcast_funding_url = xml_base::resolve(state, &attr.value);
         }
     }
 }
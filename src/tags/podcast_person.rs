@@ -1,6 +1,7 @@
 use xml::attribute::OwnedAttribute;
 
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
 use crate::models::PodcastPerson;
 
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
@@ -19,8 +20,8 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
         match attr.name.local_name.as_str() {
             "role" => state.current_person_role = attr.value.clone(),
             "group" => state.current_person_group = attr.value.clone(),
-            "img" => state.current_person_img = attr.value.clone(),
-            "href" => state.current_person_href = attr.value.clone(),
+            "img" => state.current_person_img = xml_base::resolve(state, &attr.value),
+            "href" => state.current_person_href = xml_base::resolve(state, &attr.value),
             _ => {}
         }
     }
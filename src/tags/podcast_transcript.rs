@@ -1,6 +1,8 @@
 use xml::attribute::OwnedAttribute;
 
+use crate::languages;
 use crate::parser_state::ParserState;
+use crate::tags::xml_base;
 use crate::models::PodcastTranscript;
 
 pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
@@ -10,11 +12,13 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
 
     let mut transcript_url = String::new();
     let mut transcript_type = String::new();
+    let mut transcript_language = String::new();
 
     for attr in attributes {
         match attr.name.local_name.as_str() {
-            "url" => transcript_url = attr.value.clone(),
+            "url" => transcript_url = xml_base::resolve(state, &attr.value),
             "type" => transcript_type = attr.value.clone(),
+            "language" => transcript_language = languages::column_value(&attr.value),
             _ => {}
         }
     }
@@ -22,5 +26,6 @@ pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
     state.podcast_transcripts.push(PodcastTranscript {
         url: transcript_url,
         r#type: transcript_type,
+        language: transcript_language,
     });
 }
\ No newline at end of file
@@ -1,12 +1,14 @@
 use crate::parser_state::ParserState;
-use crate::utils;
+use crate::dates;
 
 pub fn on_text(data: &str, state: &mut ParserState) {
     if state.in_item {
         if state.pub_date == 0 {
-            state.pub_date = utils::pub_date_to_timestamp(data);
+            state.pub_date_raw = data.trim().to_string();
+            state.pub_date = dates::timestamp_or_report(data, &state.current_element, &mut state.diagnostics);
         }
     } else if state.in_channel && !state.in_item && state.channel_pub_date == 0 {
-        state.channel_pub_date = utils::pub_date_to_timestamp(data);
+        state.channel_pub_date_raw = data.trim().to_string();
+        state.channel_pub_date = dates::timestamp_or_report(data, &state.current_element, &mut state.diagnostics);
     }
 }
\ No newline at end of file
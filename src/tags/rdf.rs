@@ -0,0 +1,13 @@
+use crate::parser_state::ParserState;
+use crate::tags::channel;
+
+// RSS 1.0 places <item> elements next to <channel> rather than inside it, so
+// the whole <rdf:RDF> document is treated as the channel scope.
+pub fn on_start(state: &mut ParserState) {
+    state.feed_type = 2; // rdf
+    channel::on_start(state);
+}
+
+pub fn on_end(feed_id: Option<i64>, state: &mut ParserState) {
+    channel::on_end(feed_id, state);
+}
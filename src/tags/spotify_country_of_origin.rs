@@ -0,0 +1,7 @@
+use crate::parser_state::ParserState;
+
+pub fn on_text(data: &str, state: &mut ParserState) {
+    if state.in_channel && !state.in_item && state.channel_spotify_country_of_origin.is_empty() {
+        state.channel_spotify_country_of_origin.push_str(data);
+    }
+}
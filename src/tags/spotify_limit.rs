@@ -0,0 +1,13 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    if !state.in_channel || state.in_item {
+        return;
+    }
+
+    if let Some(attr) = attributes.iter().find(|a| a.name.local_name == "recentCount") {
+        state.channel_spotify_limit = attr.value.trim().to_string();
+    }
+}
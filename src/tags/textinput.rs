@@ -0,0 +1,12 @@
+use crate::parser_state::ParserState;
+
+pub fn on_start(state: &mut ParserState) {
+    // Track <textInput> so its title/link/description don't leak into the channel
+    if state.in_channel && !state.in_item {
+        state.in_channel_textinput = true;
+    }
+}
+
+pub fn on_end(state: &mut ParserState) {
+    state.in_channel_textinput = false;
+}
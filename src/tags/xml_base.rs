@@ -0,0 +1,41 @@
+use xml::attribute::OwnedAttribute;
+
+use crate::parser_state::ParserState;
+use crate::utils;
+
+// Track element depth and any xml:base declarations so relative URLs can be
+// resolved against the closest base in scope (falling back to the feed URL).
+pub fn on_start(attributes: &[OwnedAttribute], state: &mut ParserState) {
+    state.element_depth += 1;
+
+    let base_attr = attributes.iter().find(|a| {
+        a.name.local_name == "base"
+            && (a.name.prefix.as_deref() == Some("xml")
+                || a.name.namespace.as_deref() == Some("http://www.w3.org/XML/1998/namespace"))
+    });
+
+    if let Some(attr) = base_attr {
+        let base = utils::resolve_url(current(state), attr.value.trim());
+        state.xml_bases.push((state.element_depth, base));
+    }
+}
+
+pub fn on_end(state: &mut ParserState) {
+    if matches!(state.xml_bases.last(), Some((depth, _)) if *depth == state.element_depth) {
+        state.xml_bases.pop();
+    }
+    state.element_depth = state.element_depth.saturating_sub(1);
+}
+
+// Resolve an attribute URL against the base in scope for the current element
+pub fn resolve(state: &ParserState, href: &str) -> String {
+    utils::resolve_url(current(state), href.trim())
+}
+
+pub fn current(state: &ParserState) -> &str {
+    state
+        .xml_bases
+        .last()
+        .map(|(_, base)| base.as_str())
+        .unwrap_or(state.feed_url.as_str())
+}
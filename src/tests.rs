@@ -3,8 +3,9 @@ use std::fs;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
-use chrono::{DateTime, Duration, Utc};
+use chrono::{Duration, Utc};
 use serde_json::{json, Value as JsonValue};
+use crate::urls::UrlKind;
 
 fn unique_temp_dir() -> PathBuf {
     let base = std::env::temp_dir();
@@ -21,7 +22,7 @@ fn unique_temp_dir() -> PathBuf {
 fn ensure_output_dir() -> PathBuf {
     // Use get_or_init to atomically ensure only one directory is created
     // This prevents race conditions where parallel tests create different directories
-    OUTPUT_SUBDIR.get_or_init(|| unique_temp_dir()).clone()
+    OUTPUT_SUBDIR.get_or_init(unique_temp_dir).clone()
 }
 
 fn get_value(v: &serde_json::Value, col_name: &str) -> Option<serde_json::Value> {
@@ -45,7 +46,7 @@ fn get_value(v: &serde_json::Value, col_name: &str) -> Option<serde_json::Value>
     None
 }
 
-fn sort_paths_by_numeric_prefix(paths: &mut Vec<PathBuf>) {
+fn sort_paths_by_numeric_prefix(paths: &mut [PathBuf]) {
     paths.sort_by(|a, b| {
         let an = a.file_name().and_then(|s| s.to_str()).unwrap_or("");
         let bn = b.file_name().and_then(|s| s.to_str()).unwrap_or("");
@@ -449,7 +450,7 @@ https://example.com/feed.xml
     assert_eq!(get_value(&v, "title"), Some(JsonValue::from("LastBuildDate Only Test")));
     // lastBuildDate should be used as fallback when pubDate is missing
     // Use the actual parsed timestamp value
-    let expected_timestamp = utils::pub_date_to_timestamp("Tue, 02 Jan 2024 15:30:45 GMT");
+    let expected_timestamp = dates::timestamp("Tue, 02 Jan 2024 15:30:45 GMT").unwrap();
     assert_eq!(get_value(&v, "pub_date"), Some(JsonValue::from(expected_timestamp)));
     // lastBuildDate should also be present as a separate field
     assert_eq!(get_value(&v, "last_build_date"), Some(JsonValue::from(expected_timestamp)));
@@ -489,7 +490,7 @@ https://example.com/feed.xml
     assert_eq!(get_value(&v, "feed_id"), Some(JsonValue::from(feed_id)));
     assert_eq!(get_value(&v, "title"), Some(JsonValue::from("NewestItemPubDate Fallback Test")));
     // pubDate should fallback to newestItemPubDate when both pubDate and lastBuildDate are 0
-    let expected_newest = utils::pub_date_to_timestamp("Tue, 02 Jan 2024 15:30:45 GMT");
+    let expected_newest = dates::timestamp("Tue, 02 Jan 2024 15:30:45 GMT").unwrap();
     assert_eq!(get_value(&v, "pub_date"), Some(JsonValue::from(expected_newest)));
     assert_eq!(get_value(&v, "newest_item_pub_date"), Some(JsonValue::from(expected_newest)));
     assert_eq!(get_value(&v, "last_build_date"), Some(JsonValue::from(0)));
@@ -525,13 +526,13 @@ https://example.com/feed.xml
     assert_eq!(get_value(&v, "feed_id"), Some(JsonValue::from(feed_id)));
     assert_eq!(get_value(&v, "title"), Some(JsonValue::from("PubDate Priority Test")));
     // pubDate should be used (not lastBuildDate or newestItemPubDate)
-    let expected_pubdate = utils::pub_date_to_timestamp("Mon, 01 Jan 2024 10:00:00 GMT");
+    let expected_pubdate = dates::timestamp("Mon, 01 Jan 2024 10:00:00 GMT").unwrap();
     assert_eq!(get_value(&v, "pub_date"), Some(JsonValue::from(expected_pubdate)));
     // lastBuildDate should still be present
-    let expected_lastbuild = utils::pub_date_to_timestamp("Mon, 01 Jan 2024 11:00:00 GMT");
+    let expected_lastbuild = dates::timestamp("Mon, 01 Jan 2024 11:00:00 GMT").unwrap();
     assert_eq!(get_value(&v, "last_build_date"), Some(JsonValue::from(expected_lastbuild)));
     // newestItemPubDate should be from the item
-    let expected_newest = utils::pub_date_to_timestamp("Tue, 02 Jan 2024 15:30:45 GMT");
+    let expected_newest = dates::timestamp("Tue, 02 Jan 2024 15:30:45 GMT").unwrap();
     assert_eq!(get_value(&v, "newest_item_pub_date"), Some(JsonValue::from(expected_newest)));
 }
 
@@ -564,7 +565,7 @@ https://example.com/feed.xml
     assert_eq!(get_value(&v, "feed_id"), Some(JsonValue::from(feed_id)));
     assert_eq!(get_value(&v, "title"), Some(JsonValue::from("LastBuildDate Fallback Test")));
     // pubDate should fallback to lastBuildDate (not newestItemPubDate, since lastBuildDate exists)
-    let expected_lastbuild = utils::pub_date_to_timestamp("Mon, 01 Jan 2024 11:00:00 GMT");
+    let expected_lastbuild = dates::timestamp("Mon, 01 Jan 2024 11:00:00 GMT").unwrap();
     assert_eq!(get_value(&v, "pub_date"), Some(JsonValue::from(expected_lastbuild)));
     assert_eq!(get_value(&v, "last_build_date"), Some(JsonValue::from(expected_lastbuild)));
 }
@@ -615,8 +616,8 @@ https://example.com/feed.xml
     let feed_id = 20100_i64;
     process_feed_sync(Cursor::new(feed), "test.xml", Some(feed_id));
     let v = single_record(&out_dir, "newsfeeds", feed_id);
-    let newest = utils::pub_date_to_timestamp("Tue, 02 Jan 2024 12:00:00 GMT");
-    let oldest = utils::pub_date_to_timestamp("Mon, 01 Jan 2024 12:00:00 GMT");
+    let newest = dates::timestamp("Tue, 02 Jan 2024 12:00:00 GMT").unwrap();
+    let oldest = dates::timestamp("Mon, 01 Jan 2024 12:00:00 GMT").unwrap();
     assert_eq!(get_value(&v, "item_count"), Some(JsonValue::from(2)));
     assert_eq!(get_value(&v, "newest_item_pub_date"), Some(JsonValue::from(newest)));
     assert_eq!(get_value(&v, "oldest_item_pub_date"), Some(JsonValue::from(oldest)));
@@ -710,8 +711,8 @@ fn test_newsfeeds_update_frequency_and_epoch_pubdates() {
     let out_dir = ensure_output_dir();
     let now = Utc::now();
     let recent = now - Duration::days(1);
-    let rfc_now = DateTime::<Utc>::from(now).to_rfc2822();
-    let rfc_recent = DateTime::<Utc>::from(recent).to_rfc2822();
+    let rfc_now = now.to_rfc2822();
+    let rfc_recent = recent.to_rfc2822();
     let feed = format!(r#"1700000000
 [[NO_ETAG]]
 https://example.com/feed.xml
@@ -877,7 +878,7 @@ https://example.com/feed.xml
     assert_eq!(get_value(&item, "title"), Some(JsonValue::from("Itunes Episode Title")));
     assert_eq!(get_value(&item, "link"), Some(JsonValue::from("https://example.com/ep1")));
     assert_eq!(get_value(&item, "description"), Some(JsonValue::from("Content encoded fallback")));
-    let expected_pub_date = utils::pub_date_to_timestamp("Mon, 01 Jan 2024 12:00:00 GMT");
+    let expected_pub_date = dates::timestamp("Mon, 01 Jan 2024 12:00:00 GMT").unwrap();
     assert_eq!(get_value(&item, "pub_date"), Some(JsonValue::from(expected_pub_date)));
     assert_eq!(get_value(&item, "guid"), Some(JsonValue::from("ep-guid")));
     assert_eq!(get_value(&item, "image"), Some(JsonValue::from("https://example.com/ep.jpg")));
@@ -2188,8 +2189,8 @@ https://example.com/feed.xml
 </item>
 <item>
 <title><![CDATA[Ep2 ]]></title>
-<guid>g-content</guid>
-<enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+<guid>g-content-2</guid>
+<enclosure url="https://example.com/ep2.mp3" length="1" type="audio/mpeg"/>
 </item>
 </channel>
 </rss>"#;
@@ -2245,14 +2246,14 @@ https://example.com/feed.xml
         <p>This is the full HTML content of the blog post with formatting.</p>
         <p>It can contain multiple paragraphs and rich content.</p>
       ]]></content:encoded>
-      <enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+      <enclosure url="https://example.com/ep1.mp3" length="1" type="audio/mpeg"/>
    </item>
 
     <!-- Case 3: Simple description field -->
     <item>
       <title>Simple Description</title>
       <description>This is a plain text description of the item.</description>
-      <enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+      <enclosure url="https://example.com/ep2.mp3" length="1" type="audio/mpeg"/>
     </item>
 
     <!-- Case 4: content field as array (parsed from Atom feeds) -->
@@ -2264,7 +2265,7 @@ https://example.com/feed.xml
       <content type="text">
         Second content element (would be ignored)
       </content>
-      <enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+      <enclosure url="https://example.com/ep3.mp3" length="1" type="audio/mpeg"/>
     </item>
 
     <!-- Case 5: content with #text property (from XML parsing) -->
@@ -2273,7 +2274,7 @@ https://example.com/feed.xml
       <content type="html">
         This text becomes the #text property when parsed
       </content>
-      <enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+      <enclosure url="https://example.com/ep4.mp3" length="1" type="audio/mpeg"/>
     </item>
 
     <!-- Case 6: Multiple sources - iTunes takes priority -->
@@ -2282,14 +2283,14 @@ https://example.com/feed.xml
       <itunes:summary>iTunes summary (wins)</itunes:summary>
       <description>Regular description (ignored)</description>
       <content:encoded>Content encoded</content:encoded>
-      <enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+      <enclosure url="https://example.com/ep5.mp3" length="1" type="audio/mpeg"/>
     </item>
 
     <!-- Case 7: No description at all -->
     <item>
       <title>No Description</title>
       <!-- Will result in empty string -->
-      <enclosure url="https://example.com/ep.mp3" length="1" type="audio/mpeg"/>
+      <enclosure url="https://example.com/ep6.mp3" length="1" type="audio/mpeg"/>
     </item>
 
   </channel>
@@ -2387,9 +2388,9 @@ https://example.com/feed.xml
 </channel>
 </rss>"#;
 
-    process_feed_sync(Cursor::new(feed), "test.xml", Some(33009));
+    process_feed_sync(Cursor::new(feed), "test.xml", Some(33010));
 
-    let nf = single_record(&out_dir, "newsfeeds", 33009);
+    let nf = single_record(&out_dir, "newsfeeds", 33010);
     println!("{:?}", nf);
     assert_eq!(get_value(&nf, "generator"), Some(JsonValue::from("Channel Generator")));
     assert_eq!(get_value(&nf, "link"), Some(JsonValue::from("http://example.com/link-channel")));
@@ -2422,4 +2423,2508 @@ https://example.com/feed.xml
     assert_eq!(v["table"], "nfitems");
     // itunes_author is not trimmed in current implementation
     assert_eq!(get_value(&v, "itunes_author"), Some(serde_json::json!(" Guest Speaker ")));
-}
\ No newline at end of file
+}
+
+// Media RSS content should act as an enclosure source when no enclosure exists
+#[test]
+fn test_media_rss_enclosure_fallbacks() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+  <channel>
+    <title>Media Channel</title>
+    <media:thumbnail url="https://example.com/channel-thumb.jpg"/>
+    <media:description>Channel media description</media:description>
+    <media:rating>adult</media:rating>
+    <item>
+      <title>Grouped Video</title>
+      <guid>media-1</guid>
+      <media:group>
+        <media:content url="https://example.com/low.mp4" type="video/mp4" fileSize="100" duration="61"/>
+        <media:content url="https://example.com/high.mp4" type="video/mp4" fileSize="900" duration="62" isDefault="true"/>
+        <media:thumbnail url="https://example.com/thumb.jpg"/>
+        <media:description>Video description</media:description>
+      </media:group>
+      <media:rating scheme="urn:mpaa">r</media:rating>
+    </item>
+    <item>
+      <title>Image Only</title>
+      <guid>media-2</guid>
+      <media:content url="https://example.com/photo.jpg" medium="image"/>
+    </item>
+    <item>
+      <title>Enclosure Wins</title>
+      <guid>media-3</guid>
+      <media:content url="https://example.com/media.mp3" type="audio/mpeg"/>
+      <enclosure url="https://example.com/enclosure.mp3" length="5" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 2601_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/channel-thumb.jpg")));
+    assert_eq!(get_value(&nf, "description"), Some(json!("Channel media description")));
+    assert_eq!(get_value(&nf, "explicit"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(2)));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 2);
+    assert_eq!(get_value(&items[0], "enclosure_url"), Some(json!("https://example.com/high.mp4")));
+    assert_eq!(get_value(&items[0], "enclosure_length"), Some(json!(900)));
+    assert_eq!(get_value(&items[0], "enclosure_type"), Some(json!("video/mp4")));
+    assert_eq!(get_value(&items[0], "itunes_duration"), Some(json!(62)));
+    assert_eq!(get_value(&items[0], "image"), Some(json!("https://example.com/thumb.jpg")));
+    assert_eq!(get_value(&items[0], "description"), Some(json!("Video description")));
+    assert_eq!(get_value(&items[0], "itunes_explicit"), Some(json!(1)));
+    assert_eq!(get_value(&items[1], "enclosure_url"), Some(json!("https://example.com/enclosure.mp3")));
+}
+
+// Media RSS elements should be recognized by namespace even with a custom prefix
+#[test]
+fn test_media_rss_custom_prefix() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:m="http://search.yahoo.com/mrss/">
+  <channel>
+    <title>Prefixed Media</title>
+    <item>
+      <title>Episode</title>
+      <m:content url="https://example.com/episode.m4a"/>
+      <m:rating>nonadult</m:rating>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 2602_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "enclosure_url"), Some(json!("https://example.com/episode.m4a")));
+    assert_eq!(get_value(&item, "enclosure_type"), Some(json!("audio/mp4")));
+    assert_eq!(get_value(&item, "itunes_explicit"), Some(json!(0)));
+}
+
+// Google Play tags should fill in when the iTunes equivalents are missing
+#[test]
+fn test_googleplay_fallbacks() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:gp="http://www.google.com/schemas/play-podcasts/1.0"
+ xmlns:spotify="http://www.spotify.com/ns/rss">
+  <channel>
+    <title>Legacy Channel</title>
+    <gp:author>Play Author</gp:author>
+    <gp:description>Play description</gp:description>
+    <gp:image href="https://example.com/play.jpg"/>
+    <gp:explicit>yes</gp:explicit>
+    <gp:block>yes</gp:block>
+    <spotify:limit recentCount="5"/>
+    <spotify:countryOfOrigin>US GB</spotify:countryOfOrigin>
+  </channel>
+</rss>"#;
+    let feed_id = 2701_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "itunes_author"), Some(json!("Play Author")));
+    assert_eq!(get_value(&nf, "description"), Some(json!("Play description")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/play.jpg")));
+    assert_eq!(get_value(&nf, "explicit"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "googleplay_block"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "spotify_limit"), Some(json!(5)));
+    assert_eq!(get_value(&nf, "spotify_country_of_origin"), Some(json!("us gb")));
+}
+
+// iTunes values should take precedence over the Google Play fallbacks
+#[test]
+fn test_googleplay_itunes_precedence() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+ xmlns:googleplay="http://www.google.com/schemas/play-podcasts/1.0">
+  <channel>
+    <title>Both Namespaces</title>
+    <description>RSS description</description>
+    <itunes:author>iTunes Author</itunes:author>
+    <itunes:image href="https://example.com/itunes.jpg"/>
+    <googleplay:author>Play Author</googleplay:author>
+    <googleplay:description>Play description</googleplay:description>
+    <googleplay:image href="https://example.com/play.jpg"/>
+  </channel>
+</rss>"#;
+    let feed_id = 2702_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "itunes_author"), Some(json!("iTunes Author")));
+    assert_eq!(get_value(&nf, "description"), Some(json!("RSS description")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/itunes.jpg")));
+    assert_eq!(get_value(&nf, "googleplay_block"), Some(json!(0)));
+    assert_eq!(get_value(&nf, "spotify_limit"), Some(JsonValue::Null));
+}
+
+// RSS 1.0 (RDF) feeds keep items outside of the channel element
+#[test]
+fn test_rdf_feed_support() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.rdf
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+ xmlns="http://purl.org/rss/1.0/"
+ xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel rdf:about="https://example.com/">
+    <title>RDF Channel</title>
+    <link>https://example.com/</link>
+    <description>RDF description</description>
+    <dc:language>en-gb</dc:language>
+    <dc:creator>RDF Creator</dc:creator>
+    <dc:date>2024-01-01T00:00:00Z</dc:date>
+  </channel>
+  <image rdf:about="https://example.com/logo.png">
+    <title>Logo</title>
+    <url>https://example.com/logo.png</url>
+  </image>
+  <item rdf:about="https://example.com/1">
+    <title>RDF Episode</title>
+    <link>https://example.com/1</link>
+    <dc:creator>Episode Creator</dc:creator>
+    <dc:date>2024-01-02T00:00:00Z</dc:date>
+    <enclosure url="https://example.com/1.mp3" length="10" type="audio/mpeg"/>
+  </item>
+  <textinput rdf:about="https://example.com/search">
+    <title>Search</title>
+    <description>Search this site</description>
+    <link>https://example.com/search</link>
+  </textinput>
+</rdf:RDF>"#;
+    let feed_id = 2801_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "type"), Some(json!(2)));
+    assert_eq!(get_value(&nf, "title"), Some(json!("RDF Channel")));
+    assert_eq!(get_value(&nf, "link"), Some(json!("https://example.com/")));
+    assert_eq!(get_value(&nf, "description"), Some(json!("RDF description")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/logo.png")));
+    assert_eq!(get_value(&nf, "language"), Some(json!("en-GB")));
+    assert_eq!(get_value(&nf, "language_raw"), Some(json!("en-gb")));
+    assert_eq!(get_value(&nf, "itunes_author"), Some(json!("RDF Creator")));
+    assert_eq!(get_value(&nf, "pub_date"), Some(json!(1704067200)));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(1)));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "title"), Some(json!("RDF Episode")));
+    assert_eq!(get_value(&item, "itunes_author"), Some(json!("Episode Creator")));
+    assert_eq!(get_value(&item, "pub_date"), Some(json!(1704153600)));
+}
+
+// Dublin Core values are fallbacks for RSS 2.0 feeds too
+#[test]
+fn test_dublin_core_fallbacks_in_rss2() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>DC Channel</title>
+    <language>fr</language>
+    <dc:language>de</dc:language>
+    <item>
+      <title>Dated by pubDate</title>
+      <pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>
+      <dc:date>2020-01-01T00:00:00Z</dc:date>
+      <enclosure url="https://example.com/a.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Dated by dc:date</title>
+      <dc:date>2024-01-02T00:00:00Z</dc:date>
+      <enclosure url="https://example.com/b.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 2802_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "type"), Some(json!(0)));
+    assert_eq!(get_value(&nf, "language"), Some(json!("fr")));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 2);
+    assert_eq!(get_value(&items[0], "pub_date"), Some(json!(1704067200)));
+    assert_eq!(get_value(&items[1], "pub_date"), Some(json!(1704153600)));
+}
+
+// JSON Feed payloads should map onto the same newsfeeds/nfitems records
+#[test]
+fn test_json_feed_support() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.json
+0
+{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "JSON Cast",
+  "home_page_url": "https://example.com/",
+  "feed_url": "https://example.com/feed.json",
+  "description": "A JSON feed",
+  "icon": "https://example.com/icon.png",
+  "language": "en-US",
+  "authors": [{"name": "JSON Author"}],
+  "_podcast": {"guid": "json-guid", "explicit": true},
+  "items": [
+    {
+      "id": "ep-1",
+      "url": "https://example.com/ep-1",
+      "title": "JSON Episode",
+      "content_html": "<p>Episode notes</p>",
+      "date_published": "2024-01-01T00:00:00Z",
+      "authors": [{"name": "Guest"}],
+      "attachments": [
+        {"url": "https://example.com/ep-1.mp3", "mime_type": "audio/mpeg", "size_in_bytes": 4321, "duration_in_seconds": 90}
+      ]
+    },
+    {
+      "id": "post-2",
+      "title": "Text only post",
+      "content_text": "No attachment here"
+    }
+  ]
+}"#;
+    let feed_id = 2901_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "type"), Some(json!(3)));
+    assert_eq!(get_value(&nf, "title"), Some(json!("JSON Cast")));
+    assert_eq!(get_value(&nf, "link"), Some(json!("https://example.com/")));
+    assert_eq!(get_value(&nf, "description"), Some(json!("A JSON feed")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/icon.png")));
+    assert_eq!(get_value(&nf, "itunes_author"), Some(json!("JSON Author")));
+    assert_eq!(get_value(&nf, "podcast_guid"), Some(json!("json-guid")));
+    assert_eq!(get_value(&nf, "explicit"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "pubsub_self_url"), Some(json!("https://example.com/feed.json")));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(1)));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "guid"), Some(json!("ep-1")));
+    assert_eq!(get_value(&item, "title"), Some(json!("JSON Episode")));
+    assert_eq!(get_value(&item, "description"), Some(json!("<p>Episode notes</p>")));
+    assert_eq!(get_value(&item, "pub_date"), Some(json!(1704067200)));
+    assert_eq!(get_value(&item, "itunes_author"), Some(json!("Guest")));
+    assert_eq!(get_value(&item, "enclosure_url"), Some(json!("https://example.com/ep-1.mp3")));
+    assert_eq!(get_value(&item, "enclosure_length"), Some(json!(4321)));
+    assert_eq!(get_value(&item, "itunes_duration"), Some(json!(90)));
+}
+
+#[test]
+fn test_json_feed_numeric_ids_and_bad_dates() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.json
+0
+{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Numbered",
+  "items": [
+    {"id": 101, "title": "One", "date_published": "someday",
+     "attachments": [{"url": "https://example.com/1.mp3", "mime_type": "audio/mpeg"}]},
+    {"id": 102, "title": "Two",
+     "attachments": [{"url": "https://example.com/2.mp3", "mime_type": "audio/mpeg"}]}
+  ]
+}"#;
+    let feed_id = 2902_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    let guids: Vec<_> = items.iter().map(|r| get_value(r, "guid").unwrap()).collect();
+    assert_eq!(guids, vec![json!("101"), json!("102")]);
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([{"code": "unparseable_date", "element": "date_published", "value": "someday"}]))
+    );
+}
+
+// Atom feeds get their own handlers: titles, ids, xhtml content, entry authors,
+// categories, rights, icons and xml:base resolution
+#[test]
+fn test_atom_first_class_support() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feeds/atom.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:base="https://example.com/podcast/">
+  <title>Atom Native</title>
+  <rights>© 2024 Example</rights>
+  <icon>/icon.png</icon>
+  <updated>2024-01-05T00:00:00Z</updated>
+  <category term="Technology"/>
+  <entry>
+    <id>urn:uuid:1234</id>
+    <title type="html">Episode &lt;One&gt;</title>
+    <published>2024-01-02T00:00:00Z</published>
+    <updated>2024-01-03T00:00:00Z</updated>
+    <author><name>Entry Author</name></author>
+    <category term="Interviews"/>
+    <rights>CC-BY</rights>
+    <content type="xhtml">
+      <div xmlns="http://www.w3.org/1999/xhtml"><p>Hello <a href="https://example.com/x?a=1&amp;b=2">world</a> &amp; friends</p></div>
+    </content>
+    <link href="episodes/1"/>
+    <link rel="enclosure" href="media/1.mp3" length="42" type="audio/mpeg"/>
+  </entry>
+  <entry xml:base="https://cdn.example.net/">
+    <id>urn:uuid:5678</id>
+    <title>Episode Two</title>
+    <updated>2024-01-04T00:00:00Z</updated>
+    <link rel="enclosure" href="2.mp3" type="audio/mpeg"/>
+  </entry>
+</feed>"#;
+    let feed_id = 3001_i64;
+    process_feed_sync(Cursor::new(feed), "atom.xml", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "type"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "title"), Some(json!("Atom Native")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/icon.png")));
+    assert_eq!(get_value(&nf, "rights"), Some(json!("© 2024 Example")));
+    assert_eq!(get_value(&nf, "categories"), Some(json!(["Technology"])));
+    assert_eq!(get_value(&nf, "last_build_date"), Some(json!(1704412800)));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 2);
+    assert_eq!(get_value(&items[0], "title"), Some(json!("Episode <One>")));
+    assert_eq!(get_value(&items[0], "guid"), Some(json!("urn:uuid:1234")));
+    assert_eq!(get_value(&items[0], "pub_date"), Some(json!(1704153600)));
+    assert_eq!(get_value(&items[0], "itunes_author"), Some(json!("Entry Author")));
+    assert_eq!(get_value(&items[0], "categories"), Some(json!(["Interviews"])));
+    assert_eq!(get_value(&items[0], "rights"), Some(json!("CC-BY")));
+    assert_eq!(
+        get_value(&items[0], "description"),
+        Some(json!("<p>Hello <a href=\"https://example.com/x?a=1&amp;b=2\">world</a> &amp; friends</p>"))
+    );
+    assert_eq!(get_value(&items[0], "link"), Some(json!("https://example.com/podcast/episodes/1")));
+    assert_eq!(get_value(&items[0], "enclosure_url"), Some(json!("https://example.com/podcast/media/1.mp3")));
+
+    assert_eq!(get_value(&items[1], "pub_date"), Some(json!(1704326400)));
+    assert_eq!(get_value(&items[1], "enclosure_url"), Some(json!("https://cdn.example.net/2.mp3")));
+}
+
+// Without xml:base, Atom links resolve against the feed URL from the header
+#[test]
+fn test_atom_links_resolve_against_feed_url() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feeds/atom.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Relative Atom</title>
+  <logo>logo.png</logo>
+  <link rel="alternate" href="/"/>
+  <entry>
+    <id>1</id>
+    <title>Episode</title>
+    <link rel="enclosure" href="../audio/1.mp3" type="audio/mpeg"/>
+  </entry>
+</feed>"#;
+    let feed_id = 3002_i64;
+    process_feed_sync(Cursor::new(feed), "atom.xml", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "link"), Some(json!("https://example.com/")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/feeds/logo.png")));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "enclosure_url"), Some(json!("https://example.com/audio/1.mp3")));
+}
+
+// Relative URLs in RSS feeds resolve against the feed URL or xml:base before validation
+#[test]
+fn test_rss_relative_urls_resolved() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/shows/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+ xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Relative Channel</title>
+    <link>/</link>
+    <image><url>art/cover.jpg</url></image>
+    <itunes:image href="/itunes.jpg"/>
+    <item>
+      <title>Relative Episode</title>
+      <link>episodes/1</link>
+      <enclosure url="/episodes/1.mp3" length="1" type="audio/mpeg"/>
+      <podcast:transcript url="1.vtt" type="text/vtt"/>
+    </item>
+    <item xml:base="https://cdn.example.org/audio/">
+      <title>Based Episode</title>
+      <enclosure url="2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3101_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "link"), Some(json!("https://example.com/")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/shows/art/cover.jpg")));
+    assert_eq!(get_value(&nf, "itunes_image"), Some(json!("https://example.com/itunes.jpg")));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(2)));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 2);
+    assert_eq!(get_value(&items[0], "link"), Some(json!("https://example.com/shows/episodes/1")));
+    assert_eq!(get_value(&items[0], "enclosure_url"), Some(json!("https://example.com/episodes/1.mp3")));
+    assert_eq!(
+        get_value(&items[0], "podcast_transcripts"),
+        Some(json!([{"url": "https://example.com/shows/1.vtt", "type": "text/vtt", "language": ""}]))
+    );
+    assert_eq!(get_value(&items[1], "enclosure_url"), Some(json!("https://cdn.example.org/audio/2.mp3")));
+}
+
+#[test]
+fn test_json_feed_relative_urls_resolved() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/shows/feed.json
+0
+{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Relative JSON",
+  "home_page_url": "/",
+  "feed_url": "feed.json",
+  "icon": "art/icon.png",
+  "hubs": [{"type": "WebSub", "url": "/hub"}],
+  "_podcast": {"funding": {"url": "/support", "message": "Support us"}},
+  "items": [
+    {
+      "id": "ep-1",
+      "url": "episodes/1",
+      "banner_image": "art/ep1.jpg",
+      "attachments": [{"url": "/audio/1.mp3", "mime_type": "audio/mpeg"}]
+    }
+  ]
+}"#;
+    let feed_id = 3102_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "link"), Some(json!("https://example.com/")));
+    assert_eq!(get_value(&nf, "pubsub_self_url"), Some(json!("https://example.com/shows/feed.json")));
+    assert_eq!(get_value(&nf, "image"), Some(json!("https://example.com/shows/art/icon.png")));
+    assert_eq!(get_value(&nf, "pubsub_hub_url"), Some(json!("https://example.com/hub")));
+    assert_eq!(get_value(&nf, "podcast_funding_url"), Some(json!("https://example.com/support")));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "link"), Some(json!("https://example.com/shows/episodes/1")));
+    assert_eq!(get_value(&item, "image"), Some(json!("https://example.com/shows/art/ep1.jpg")));
+    assert_eq!(get_value(&item, "enclosure_url"), Some(json!("https://example.com/audio/1.mp3")));
+}
+
+#[test]
+fn test_url_normalization_and_rejection() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>URL Channel</title>
+    <link>https://bücher.example/über uns#team</link>
+    <item>
+      <title>Fragment Episode</title>
+      <link>https://example.com/ep/1#comments</link>
+      <enclosure url="  https://example.com/audio/épisode.mp3#t=10 " length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>FTP Episode</title>
+      <enclosure url="ftp://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3201_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "link"), Some(json!("https://xn--bcher-kva.example/%C3%BCber%20uns#team")));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(1)));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 1);
+    assert_eq!(get_value(&items[0], "link"), Some(json!("https://example.com/ep/1#comments")));
+    assert_eq!(
+        get_value(&items[0], "enclosure_url"),
+        Some(json!("https://example.com/audio/%C3%A9pisode.mp3"))
+    );
+
+    assert_eq!(urls::normalize("  ", UrlKind::Link), Err(urls::UrlRejection::Empty));
+    assert_eq!(
+        urls::normalize("ftp://example.com/a.mp3", UrlKind::Media),
+        Err(urls::UrlRejection::NonHttpScheme("ftp".to_string()))
+    );
+    assert!(matches!(urls::normalize("not a url", UrlKind::Link), Err(urls::UrlRejection::Unparseable(_))));
+    let long = format!("https://example.com/{}", "a".repeat(urls::MAX_URL_LENGTH));
+    assert_eq!(urls::normalize(&long, UrlKind::Link), Ok(long[..urls::MAX_URL_LENGTH].to_string()));
+}
+
+#[test]
+fn test_long_urls_truncated_and_rejections_reported() {
+    let out_dir = ensure_output_dir();
+    let long_enclosure = format!("https://cdn.example.com/ep1.mp3?token={}", "x".repeat(900));
+    let long_link = format!("https://example.com/ep/1?utm={}", "y".repeat(900));
+    let feed = format!(r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Long URLs</title>
+    <link>javascript:alert(1)</link>
+    <item>
+      <title>Signed Episode</title>
+      <guid>ep-1</guid>
+      <link>{long_link}</link>
+      <enclosure url="{long_enclosure}" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>FTP Episode</title>
+      <guid>ep-2</guid>
+      <enclosure url="ftp://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#);
+    let feed_id = 3202_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "enclosure_url"), Some(json!(long_enclosure[..urls::MAX_URL_LENGTH])));
+    assert_eq!(get_value(&item, "link"), Some(json!(long_link[..urls::MAX_URL_LENGTH])));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "link"), Some(json!("")));
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([
+            {
+                "code": "invalid_url",
+                "element": "enclosure",
+                "value": "ftp://example.com/2.mp3 (URL scheme 'ftp' is not http or https)"
+            },
+            {
+                "code": "invalid_url",
+                "element": "link",
+                "value": "javascript:alert(1) (URL scheme 'javascript' is not http or https)"
+            },
+        ]))
+    );
+}
+
+#[test]
+fn test_description_html_and_text_outputs() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>HTML Channel</title>
+    <description><![CDATA[<p style="color:red">Weekly&nbsp;show</p><script>alert(1)</script>]]></description>
+    <item>
+      <title>HTML Episode</title>
+      <content:encoded><![CDATA[<p onclick="x()">Hello <b>world</b> &amp; friends</p>
+<img src="https://tracker.example/pixel.gif" width="1" height="1"/><ul><li>One</li><li>Two</li></ul>
+<a href="javascript:alert(1)">bad</a> <a href="https://example.com/notes">notes</a>]]></content:encoded>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3301_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "description_html"), Some(json!("<p>Weekly&nbsp;show</p>")));
+    assert_eq!(get_value(&nf, "description_text"), Some(json!("Weekly show")));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 1);
+    let html = get_value(&items[0], "description_html").unwrap();
+    let html = html.as_str().unwrap();
+    assert!(html.starts_with("<p>Hello <b>world</b> &amp; friends</p>"));
+    assert!(!html.contains("onclick"));
+    assert!(!html.contains("<img"));
+    assert!(!html.contains("javascript:"));
+    assert!(html.contains(r#"<a href="https://example.com/notes" rel="noopener noreferrer nofollow">notes</a>"#));
+    assert_eq!(
+        get_value(&items[0], "description_text"),
+        Some(json!("Hello world & friends One Two bad notes"))
+    );
+}
+
+#[test]
+fn test_lenient_date_corpus() {
+    // 2023-01-01 10:00:00 UTC
+    let base = 1672567200_i64;
+    let corpus: Vec<(&str, i64)> = vec![
+        ("Sun, 01 Jan 2023 10:00:00 GMT", base),
+        ("Sun, 01 Jan 2023 10:00:00 +0000", base),
+        ("2023-01-01T10:00:00Z", base),
+        ("2023-01-01T10:00:00+00:00", base),
+        ("1672567200", base),
+        // named zones
+        ("Sun, 01 Jan 2023 06:00:00 EDT", base),
+        ("Sun, 01 Jan 2023 05:00:00 EST", base),
+        ("Sun, 01 Jan 2023 02:00:00 PST", base),
+        ("Sun, 01 Jan 2023 12:00:00 CEST", base),
+        ("Sun, 01 Jan 2023 15:30:00 IST", base),
+        // missing seconds, loose offsets
+        ("Sun, 01 Jan 2023 10:00 GMT", base),
+        ("Sun, 1 Jan 2023 12:00 GMT+2", base),
+        ("Sun, 1 Jan 2023 15:30 UTC+05:30", base),
+        ("Sun, 01 Jan 2023 05:00:00 -0500", base),
+        // two-digit years and day names that don't match the date
+        ("Sun, 01 Jan 23 10:00:00 GMT", base),
+        ("Mon, 01 Jan 2023 10:00:00 GMT", base),
+        // localized day and month names
+        ("Dom, 01 Ene 2023 10:00:00 GMT", base),
+        ("So., 01 Jan. 2023 10:00:00 +0000", base),
+        ("Mar, 03 Ene 2023 10:00:00 GMT", base + 2 * 86400),
+        ("dim., 01 janv. 2023 10:00:00 GMT", base),
+        // full month names, month first, 12-hour clock
+        ("Sunday, January 1, 2023 10:00:00 GMT", base),
+        ("January 1 2023 10:00 AM GMT", base),
+        ("Jan 1, 2023 10:00 PM", base + 12 * 3600),
+        // ISO 8601 without offsets
+        ("2023-01-01T10:00:00", base),
+        ("2023-01-01 10:00:00", base),
+        ("2023-01-01T10:00", base),
+        ("2023-01-01 12:00:00 +0200", base),
+        ("2023-01-01T10:00:00.500Z", base),
+        ("2023-01-01", base - 10 * 3600),
+        ("1 January 2023", base - 10 * 3600),
+    ];
+
+    for (input, expected) in corpus {
+        assert_eq!(dates::timestamp(input), Some(expected), "parsing {:?}", input);
+    }
+
+    for junk in ["", "   ", "not a date", "32 Jan 2023", "2023-13-01", "Sun, 01 Foo 2023 10:00:00 GMT", "soon"] {
+        assert_eq!(dates::timestamp(junk), None, "parsing {:?}", junk);
+    }
+}
+
+#[test]
+fn test_unparseable_dates_reported_in_diagnostics() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Date Channel</title>
+    <lastBuildDate>yesterday-ish</lastBuildDate>
+    <item>
+      <title>Good Date</title>
+      <pubDate>Sun, 1 Jan 2023 10:00 EDT</pubDate>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Bad Date</title>
+      <pubDate>Episode 2 release</pubDate>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3401_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 2);
+    assert_eq!(get_value(&items[0], "pub_date"), Some(json!(1672581600)));
+    assert_eq!(get_value(&items[1], "pub_date"), Some(json!(0)));
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([
+            {"code": "unparseable_date", "element": "lastBuildDate", "value": "yesterday-ish"},
+            {"code": "unparseable_date", "element": "pubDate", "value": "Episode 2 release"}
+        ]))
+    );
+}
+
+#[test]
+fn test_pub_date_offset_and_raw_columns() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Offset Channel</title>
+    <pubDate>Sun, 01 Jan 2023 06:00:00 -0400</pubDate>
+    <item>
+      <title>Eastern</title>
+      <pubDate>  Sun, 01 Jan 2023 06:00 EDT </pubDate>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>India</title>
+      <dc:date>2023-01-01T15:30:00+05:30</dc:date>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>No Zone</title>
+      <pubDate>2023-01-01 10:00:00</pubDate>
+      <enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Unreadable</title>
+      <pubDate>next tuesday</pubDate>
+      <enclosure url="https://example.com/4.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3501_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "pub_date"), Some(json!(1672567200)));
+    assert_eq!(get_value(&nf, "pub_date_offset"), Some(json!(-240)));
+    assert_eq!(get_value(&nf, "pub_date_raw"), Some(json!("Sun, 01 Jan 2023 06:00:00 -0400")));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 4);
+    assert_eq!(get_value(&items[0], "pub_date"), Some(json!(1672567200)));
+    assert_eq!(get_value(&items[0], "pub_date_offset"), Some(json!(-240)));
+    assert_eq!(get_value(&items[0], "pub_date_raw"), Some(json!("Sun, 01 Jan 2023 06:00 EDT")));
+    assert_eq!(get_value(&items[1], "pub_date"), Some(json!(1672567200)));
+    assert_eq!(get_value(&items[1], "pub_date_offset"), Some(json!(330)));
+    assert_eq!(get_value(&items[1], "pub_date_raw"), Some(json!("2023-01-01T15:30:00+05:30")));
+    assert_eq!(get_value(&items[2], "pub_date_offset"), Some(JsonValue::Null));
+    assert_eq!(get_value(&items[2], "pub_date_raw"), Some(json!("2023-01-01 10:00:00")));
+    assert_eq!(get_value(&items[3], "pub_date"), Some(json!(0)));
+    assert_eq!(get_value(&items[3], "pub_date_offset"), Some(JsonValue::Null));
+    assert_eq!(get_value(&items[3], "pub_date_raw"), Some(json!("next tuesday")));
+}
+
+#[test]
+fn test_duration_parsing_corpus() {
+    let corpus: Vec<(&str, Option<i64>)> = vec![
+        ("3600", Some(3600)),
+        (" 62.5 ", Some(63)),
+        ("01:02", Some(62)),
+        ("62:03", Some(3723)),
+        ("1:02:03", Some(3723)),
+        ("1:02:03.500", Some(3724)),
+        ("45 min", Some(2700)),
+        ("45mins", Some(2700)),
+        ("1h 20m", Some(4800)),
+        ("1 hr 20 min 5 sec", Some(4805)),
+        ("90s", Some(90)),
+        ("2 hours", Some(7200)),
+        ("PT1H2M3S", Some(3723)),
+        ("PT45M", Some(2700)),
+        ("pt1.5h", Some(5400)),
+        ("P1DT2H", Some(93600)),
+        ("4000000000", Some(4000000000)),
+        ("", None),
+        ("unknown", None),
+        ("-5", None),
+        ("1:xx", None),
+        ("1:2:3:4", None),
+        ("P1M", None),
+        ("45 parsecs", None),
+    ];
+
+    for (input, expected) in corpus {
+        assert_eq!(durations::parse(input), expected, "parsing {:?}", input);
+    }
+}
+
+#[test]
+fn test_itunes_duration_source() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Duration Channel</title>
+    <item>
+      <title>Declared</title>
+      <itunes:duration>1h 20m</itunes:duration>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Estimated</title>
+      <itunes:duration>about half an hour</itunes:duration>
+      <enclosure url="https://example.com/2.mp3" length="28800000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Missing</title>
+      <enclosure url="https://example.com/3.mp4" length="28800000" type="video/mp4"/>
+    </item>
+    <item>
+      <title>Huge</title>
+      <itunes:duration>4000000000</itunes:duration>
+      <enclosure url="https://example.com/4.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3601_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 4);
+    assert_eq!(get_value(&items[0], "itunes_duration"), Some(json!(4800)));
+    assert_eq!(get_value(&items[0], "itunes_duration_source"), Some(json!("declared")));
+    assert_eq!(get_value(&items[1], "itunes_duration"), Some(json!(1800)));
+    assert_eq!(get_value(&items[1], "itunes_duration_source"), Some(json!("estimated")));
+    assert_eq!(get_value(&items[2], "itunes_duration"), Some(json!(0)));
+    assert_eq!(get_value(&items[2], "itunes_duration_source"), Some(json!("missing")));
+    assert_eq!(get_value(&items[3], "itunes_duration"), Some(json!(i32::MAX)));
+    assert_eq!(get_value(&items[3], "itunes_duration_source"), Some(json!("declared")));
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([{"code": "unparseable_duration", "element": "itunes:duration", "value": "about half an hour"}]))
+    );
+}
+
+#[test]
+fn test_language_normalization_corpus() {
+    let corpus: Vec<(&str, Option<&str>)> = vec![
+        ("en", Some("en")),
+        ("en-us", Some("en-US")),
+        ("EN_gb", Some("en-GB")),
+        ("eng", Some("en")),
+        ("English", Some("en")),
+        ("Español", Some("es")),
+        ("fr-FR,en", Some("fr-FR")),
+        (" de-de; en ", Some("de-DE")),
+        ("zh-hant-tw", Some("zh-Hant-TW")),
+        ("ger", Some("de")),
+        ("iw", Some("he")),
+        ("es-419", Some("es-419")),
+        ("yue", Some("yue")),
+        ("", None),
+        ("abcdefghijklmnop", None),
+        ("e", None),
+        ("en-", None),
+    ];
+
+    for (input, expected) in corpus {
+        assert_eq!(languages::normalize(input).as_deref(), expected, "normalizing {:?}", input);
+    }
+
+    assert_eq!(languages::fit_column("zh-Hant-TW"), "zh-Hant");
+    assert_eq!(languages::fit_column("en-US"), "en-US");
+}
+
+#[test]
+fn test_language_columns_and_attribute_normalization() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Language Channel</title>
+    <language>EN_us</language>
+    <item>
+      <title>Episode</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+      <podcast:transcript url="https://example.com/1.vtt" type="text/vtt" language="spa"/>
+      <podcast:transcript url="https://example.com/1.srt" type="application/srt" language="zh-Hant-TW"/>
+      <podcast:transcript url="https://example.com/1.json" type="application/json" language="Klingonese"/>
+      <podcast:alternateEnclosure type="audio/opus" length="1000" bitrate="64000" lang="French" title="Version française" default="true">
+        <podcast:source uri="https://example.com/1-fr.opus"/>
+        <podcast:source uri="/mirror/1-fr.opus"/>
+        <podcast:source uri="javascript:alert(1)"/>
+        <podcast:source uri="data:audio/ogg;base64,AAAA"/>
+      </podcast:alternateEnclosure>
+      <podcast:alternateEnclosure type="video/mp4" title="LONG_TITLE" lang="zh-Hant-TW">
+        <podcast:source uri="https://example.com/1.mp4"/>
+      </podcast:alternateEnclosure>
+    </item>
+  </channel>
+</rss>"#
+        .replace("LONG_TITLE", &"t".repeat(300));
+    let feed_id = 3701_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "language"), Some(json!("en-US")));
+    assert_eq!(get_value(&nf, "language_raw"), Some(json!("EN_us")));
+
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(
+        get_value(&item, "podcast_transcripts"),
+        Some(json!([
+            {"url": "https://example.com/1.vtt", "type": "text/vtt", "language": "es"},
+            {"url": "https://example.com/1.srt", "type": "application/srt", "language": "zh-Hant"},
+            {"url": "https://example.com/1.json", "type": "application/json", "language": "Klingone"},
+        ]))
+    );
+    assert_eq!(
+        get_value(&item, "podcast_alternate_enclosures"),
+        Some(json!([{
+            "type": "audio/opus",
+            "length": "1000",
+            "bitrate": "64000",
+            "height": "",
+            "lang": "fr",
+            "title": "Version française",
+            "rel": "",
+            "default": true,
+            "sources": ["https://example.com/1-fr.opus", "https://example.com/mirror/1-fr.opus"]
+        }, {
+            "type": "video/mp4",
+            "length": "",
+            "bitrate": "",
+            "height": "",
+            "lang": "zh-Hant",
+            "title": "t".repeat(128),
+            "rel": "",
+            "default": false,
+            "sources": ["https://example.com/1.mp4"]
+        }]))
+    );
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([
+            {
+                "code": "invalid_url",
+                "element": "podcast:source",
+                "value": "javascript:alert(1) (URL scheme 'javascript' is not http or https)"
+            },
+            {
+                "code": "invalid_url",
+                "element": "podcast:source",
+                "value": "data:audio/ogg;base64,AAAA (URL scheme 'data' is not http or https)"
+            },
+        ]))
+    );
+}
+
+#[test]
+fn test_explicit_status_tri_state_and_inheritance() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+ xmlns:media="http://search.yahoo.com/mrss/">
+  <channel>
+    <title>Explicit Channel</title>
+    <itunes:explicit>clean</itunes:explicit>
+    <item>
+      <title>Inherits</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Declared Explicit</title>
+      <itunes:explicit>yes</itunes:explicit>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Rated Adult</title>
+      <itunes:explicit>false</itunes:explicit>
+      <media:rating>adult</media:rating>
+      <enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Nonsense Value</title>
+      <itunes:explicit>maybe</itunes:explicit>
+      <enclosure url="https://example.com/4.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3801_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "explicit"), Some(json!(0)));
+    assert_eq!(get_value(&nf, "explicit_status"), Some(json!("clean")));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 4);
+    let statuses: Vec<_> = items
+        .iter()
+        .map(|i| (get_value(i, "itunes_explicit").unwrap(), get_value(i, "explicit_status").unwrap()))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (json!(0), json!("clean")),
+            (json!(1), json!("explicit")),
+            (json!(1), json!("explicit")),
+            (json!(0), json!("clean")),
+        ]
+    );
+}
+
+#[test]
+fn test_channel_explicit_after_items_is_inherited() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Tags Last</title>
+    <item>
+      <title>Inherits</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Declared Clean</title>
+      <itunes:explicit>no</itunes:explicit>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <itunes:explicit>yes</itunes:explicit>
+  </channel>
+</rss>"#;
+    let feed_id = 3802_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    let statuses: Vec<_> = items
+        .iter()
+        .map(|i| (get_value(i, "itunes_explicit").unwrap(), get_value(i, "explicit_status").unwrap()))
+        .collect();
+    assert_eq!(statuses, vec![(json!(0), json!("explicit")), (json!(0), json!("clean"))]);
+}
+
+#[test]
+fn test_explicit_status_unspecified_without_declaration() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Undeclared Channel</title>
+    <item>
+      <title>Undeclared</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3803_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "explicit"), Some(json!(0)));
+    assert_eq!(get_value(&nf, "explicit_status"), Some(json!("unspecified")));
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "itunes_explicit"), Some(json!(0)));
+    assert_eq!(get_value(&item, "explicit_status"), Some(json!("unspecified")));
+}
+
+#[test]
+fn test_mime_table_and_declared_type_fixes() {
+    let corpus: Vec<(&str, &str, &str)> = vec![
+        ("", "https://example.com/ep.opus", "audio/opus"),
+        ("", "https://example.com/ep.FLAC?token=abc", "audio/flac"),
+        ("", "https://example.com/live/index.m3u8", "application/vnd.apple.mpegurl"),
+        ("", "https://example.com/stream/manifest.mpd", "application/dash+xml"),
+        ("", "https://example.com/ep.mkv", "video/x-matroska"),
+        ("", "https://example.com/ep.webm#t=10", "video/webm"),
+        ("", "https://mp3.example.com/episode", ""),
+        ("", "https://example.com/play?file=ep.mp3", ""),
+        ("audio/mp3", "https://example.com/ep", "audio/mpeg"),
+        ("Audio/X-M4A", "https://example.com/ep.m4a", "audio/mp4"),
+        ("application/octet-stream", "https://example.com/ep.mp3", "audio/mpeg"),
+        ("application/octet-stream", "https://example.com/ep", "application/octet-stream"),
+        ("audio/mpeg; charset=binary", "https://example.com/ep.mp3", "audio/mpeg"),
+        ("mp3", "https://example.com/ep.mp3", "audio/mpeg"),
+        ("video/mp4", "https://example.com/ep.mp3", "video/mp4"),
+    ];
+
+    for (declared, url, expected) in corpus {
+        assert_eq!(mime::normalize(declared, url), expected, "normalizing {:?} for {:?}", declared, url);
+    }
+}
+
+#[test]
+fn test_enclosure_type_declared_column() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Mime Channel</title>
+    <item>
+      <title>Misspelled</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mp3"/>
+    </item>
+    <item>
+      <title>Generic</title>
+      <enclosure url="https://example.com/2.m4a?x=.mp3" length="1" type="application/octet-stream"/>
+    </item>
+    <item>
+      <title>Undeclared</title>
+      <enclosure url="https://example.com/3.opus" length="1"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 3901_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 3);
+    let types: Vec<_> = items
+        .iter()
+        .map(|i| (get_value(i, "enclosure_type").unwrap(), get_value(i, "enclosure_type_declared").unwrap()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            (json!("audio/mpeg"), json!("audio/mp3")),
+            (json!("audio/mp4"), json!("application/octet-stream")),
+            (json!("audio/opus"), json!("")),
+        ]
+    );
+}
+
+#[test]
+fn test_media_kind_container_and_dominant_kind() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Kind Channel</title>
+    <item>
+      <title>Video 1</title>
+      <enclosure url="https://example.com/1.mp4" length="1" type="video/mp4"/>
+    </item>
+    <item>
+      <title>Stream</title>
+      <enclosure url="https://example.com/live/index.m3u8" length="1"/>
+    </item>
+    <item>
+      <title>Video 2</title>
+      <enclosure url="https://example.com/2.mkv" length="1"/>
+    </item>
+    <item>
+      <title>Audio</title>
+      <enclosure url="https://example.com/3.opus" length="1"/>
+    </item>
+    <item>
+      <title>Notes</title>
+      <enclosure url="https://example.com/notes.pdf" length="1"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 4001_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    let kinds: Vec<_> = items
+        .iter()
+        .map(|i| (get_value(i, "media_kind").unwrap(), get_value(i, "container").unwrap()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (json!("video"), json!("mp4")),
+            (json!("stream"), json!("hls")),
+            (json!("video"), json!("matroska")),
+            (json!("audio"), json!("ogg")),
+            (json!("document"), json!("pdf")),
+        ]
+    );
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "podcast_medium"), Some(json!("")));
+    assert_eq!(get_value(&nf, "dominant_media_kind"), Some(json!("video")));
+}
+
+#[test]
+fn test_podcast_medium_declared() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Music Channel</title>
+    <podcast:medium>Music</podcast:medium>
+    <item>
+      <title>Track</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 4002_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "podcast_medium"), Some(json!("music")));
+    assert_eq!(get_value(&nf, "dominant_media_kind"), Some(json!("audio")));
+}
+
+#[test]
+fn test_encoding_declared_windows_1252() {
+    let out_dir = ensure_output_dir();
+    let feed = b"0\n[[NO_ETAG]]\nhttps://example.com/feed.xml\n0\n\
+<?xml version=\"1.0\" encoding=\"windows-1252\"?>\
+<rss version=\"2.0\"><channel><title>Caf\xe9 \x93Talk\x94</title>\
+<item><title>Ep</title><enclosure url=\"https://example.com/1.mp3\" length=\"1\" type=\"audio/mpeg\"/></item>\
+</channel></rss>";
+    let feed_id = 4101_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Café \u{201c}Talk\u{201d}")));
+    assert_eq!(get_value(&nf, "encoding_detected"), Some(json!("windows-1252")));
+    assert_eq!(get_value(&nf, "encoding_declared"), Some(json!("windows-1252")));
+}
+
+#[test]
+fn test_encoding_mislabeled_utf8_and_undeclared() {
+    let out_dir = ensure_output_dir();
+
+    // declared Latin-1 but the bytes are UTF-8
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="ISO-8859-1"?>
+<rss version="2.0"><channel><title>Café</title>
+<item><title>Ep</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+    let feed_id = 4102_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Café")));
+    assert_eq!(get_value(&nf, "encoding_detected"), Some(json!("UTF-8")));
+    assert_eq!(get_value(&nf, "encoding_declared"), Some(json!("ISO-8859-1")));
+
+    // no declaration at all
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<rss version="2.0"><channel><title>Café</title>
+<item><title>Ep</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+    let feed_id = 4103_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Café")));
+    assert_eq!(get_value(&nf, "encoding_detected"), Some(json!("UTF-8")));
+    assert_eq!(get_value(&nf, "encoding_declared"), Some(json!("")));
+}
+
+#[test]
+fn test_encoding_bom_and_shift_jis() {
+    let out_dir = ensure_output_dir();
+
+    // UTF-16LE with a BOM and no declaration
+    let text = "<rss version=\"2.0\"><channel><title>Über</title><item><title>Ep</title><enclosure url=\"https://example.com/1.mp3\" length=\"1\" type=\"audio/mpeg\"/></item></channel></rss>";
+    let mut feed = b"0\n[[NO_ETAG]]\nhttps://example.com/feed.xml\n0\n".to_vec();
+    feed.extend_from_slice(&[0xFF, 0xFE]);
+    for unit in text.encode_utf16() {
+        feed.extend_from_slice(&unit.to_le_bytes());
+    }
+    let feed_id = 4104_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Über")));
+    assert_eq!(get_value(&nf, "encoding_detected"), Some(json!("UTF-16LE")));
+
+    // "日本語" in Shift_JIS
+    let feed = b"0\n[[NO_ETAG]]\nhttps://example.com/feed.xml\n0\n\
+<?xml version=\"1.0\" encoding=\"Shift_JIS\"?>\
+<rss version=\"2.0\"><channel><title>\x93\xfa\x96\x7b\x8c\xea</title>\
+<item><title>Ep</title><enclosure url=\"https://example.com/1.mp3\" length=\"1\" type=\"audio/mpeg\"/></item>\
+</channel></rss>";
+    let feed_id = 4105_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("日本語")));
+    assert_eq!(get_value(&nf, "encoding_detected"), Some(json!("Shift_JIS")));
+}
+
+#[test]
+fn test_recovery_clean_fixes() {
+    let entities = utils::add_html_entities_to_parser_config(xml::reader::ParserConfig::new()).extra_entities;
+    let raw = "Warning: headers sent\n<?xml version=\"1.0\"?><rss><channel><title>Q&A &#38; &bogus; &eacute;t\u{1}\u{e9}</title>\
+<description><![CDATA[a & b]]></description></channel></rss>\n<!-- cache -->junk";
+    let (cleaned, fixes) = recovery::clean(raw, &entities);
+
+    assert_eq!(
+        cleaned,
+        "<?xml version=\"1.0\"?><rss><channel><title>Q&amp;A &#38; &amp;bogus; \u{e9}t\u{e9}</title>\
+<description><![CDATA[a & b]]></description></channel></rss>"
+    );
+    assert_eq!(
+        fixes,
+        vec!["leading_garbage", "control_characters", "bare_ampersand", "undefined_entity", "trailing_garbage"]
+    );
+
+    let (cleaned, fixes) = recovery::clean("<rss><channel/></rss>", &entities);
+    assert_eq!(cleaned, "<rss><channel/></rss>");
+    assert!(fixes.is_empty());
+}
+
+#[test]
+fn test_malformed_feed_recovered() {
+    let out_dir = ensure_output_dir();
+    let feed = "0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<br />
+<b>Notice</b>: Undefined index in feed.php<br />
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<rss version=\"2.0\">
+  <channel>
+    <title>Q&A Hour\u{1}</title>
+    <item>
+      <title>Tips &amp; tricks &bogus;</title>
+      <enclosure url=\"https://example.com/1.mp3?a=1&b=2\" length=\"1\" type=\"audio/mpeg\"/>
+    </item>
+    <item>
+      <title>Ep 2 &mdash; more</title>
+      <enclosure url=\"https://example.com/2.mp3\" length=\"1\" type=\"audio/mpeg\"/>
+    </item>
+  </channel>
+</rss>";
+    let feed_id = 4201_i64;
+    let outcome = process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    assert_eq!(outcome.status, models::FeedStatus::Recovered);
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Q&A Hour")));
+    assert_eq!(get_value(&nf, "parse_status"), Some(json!("recovered")));
+    assert_eq!(get_value(&nf, "parse_error"), Some(json!("")));
+
+    // the failed first attempt must not leave records behind
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 2);
+    let titles: Vec<_> = items.iter().map(|r| get_value(r, "title")).collect();
+    assert!(titles.contains(&Some(json!("Tips & tricks &bogus;"))));
+    assert!(titles.contains(&Some(json!("Ep 2 \u{2014} more"))));
+    let urls: Vec<_> = items.iter().map(|r| get_value(r, "enclosure_url")).collect();
+    assert!(urls.contains(&Some(json!("https://example.com/1.mp3?a=1&b=2"))));
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    let fixes: Vec<_> = get_value(&diag, "diagnostics")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["code"] == "xml_recovered")
+        .map(|d| d["value"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(fixes, vec!["leading_garbage", "control_characters", "bare_ampersand", "undefined_entity"]);
+}
+
+#[test]
+fn test_malformed_feed_partial() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Broken Feed</title>
+    <item>
+      <title>Ep 1</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Ep 2</title>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </itme>
+    <item>
+      <title>Ep 3</title>
+      <enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 4202_i64;
+    let outcome = process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    assert_eq!(outcome.status, models::FeedStatus::Partial);
+    assert!(outcome.error.is_some());
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 1);
+    assert_eq!(get_value(&items[0], "title"), Some(json!("Ep 1")));
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Broken Feed")));
+    assert_eq!(get_value(&nf, "parse_status"), Some(json!("partial")));
+    assert_ne!(get_value(&nf, "parse_error"), Some(json!("")));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(1)));
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    let codes: Vec<_> = get_value(&diag, "diagnostics")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["code"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(codes, vec!["xml_error"]);
+}
+
+#[test]
+fn test_malformed_document_without_channel_fails() {
+    let out_dir = ensure_output_dir();
+    let pages = [
+        (4203_i64, "<html><head><title>502 Bad Gateway</title></head><body><p>Try again<br></body></html>"),
+        (4204_i64, "404 Not Found"),
+    ];
+    for (feed_id, page) in pages {
+        let feed = format!("0\n[[NO_ETAG]]\nhttps://example.com/feed.xml\n0\n{page}");
+        let outcome = process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+        assert_eq!(outcome.status, models::FeedStatus::Failed, "parsing {:?}", page);
+        assert!(outcome.error.is_some());
+        assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+        assert!(output_records(&out_dir, "diagnostics", feed_id).is_empty());
+    }
+}
+
+#[test]
+fn test_limits_stop_at_item_count_and_depth() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Limited</title><description>Café society podcast</description>
+<item><title>Ep 1</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 2</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 3</title><enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    let options = FeedOptions { limits: ParseLimits { max_items: 2, ..Default::default() }, ..Default::default() };
+    let feed_id = 4301_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Partial);
+    assert_eq!(outcome.limits_hit, vec![Limit::ItemCount]);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 2);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "title"), Some(json!("Limited")));
+    assert_eq!(get_value(&nf, "parse_status"), Some(json!("partial")));
+    assert_eq!(get_value(&nf, "limits_hit"), Some(json!(["item_count"])));
+
+    // rss > channel > item > title is depth 4
+    let options = FeedOptions { limits: ParseLimits { max_depth: 3, ..Default::default() }, ..Default::default() };
+    let feed_id = 4302_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Partial);
+    assert_eq!(outcome.limits_hit, vec![Limit::Depth]);
+    assert!(output_records(&out_dir, "nfitems", feed_id).is_empty());
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([{"code": "limit_exceeded", "element": "title", "value": "depth"}]))
+    );
+
+    // the first item ends at the 7th element
+    let options = FeedOptions { limits: ParseLimits { max_elements: 8, ..Default::default() }, ..Default::default() };
+    let feed_id = 4303_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.limits_hit, vec![Limit::ElementCount]);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 1);
+}
+
+#[test]
+fn test_limits_truncate_text_attributes_and_payload() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Limited</title><description>Café society podcast</description>
+<item><title>Ep 1</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 2</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 3</title><enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    // the cut lands inside the two-byte é, which is dropped whole
+    let limits = ParseLimits { max_text_length: 4, max_attributes: 2, ..Default::default() };
+    let options = FeedOptions { limits, ..Default::default() };
+    let feed_id = 4304_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Complete);
+    assert_eq!(outcome.limits_hit, vec![Limit::TextLength, Limit::AttributeCount]);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "description"), Some(json!("Caf")));
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&items[0], "title"), Some(json!("Ep 1")));
+    assert_eq!(get_value(&items[0], "enclosure_length"), Some(json!(1)));
+
+    // payload cut off in the middle of the second item
+    let header_len = feed.match_indices('\n').nth(3).unwrap().0 + 1;
+    let cut = feed.find("<item><title>Ep 2").unwrap() + 10 - header_len;
+    let options = FeedOptions { limits: ParseLimits { max_payload_bytes: cut, ..Default::default() }, ..Default::default() };
+    let feed_id = 4305_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Partial);
+    assert_eq!(outcome.limits_hit, vec![Limit::PayloadSize]);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 1);
+    assert_eq!(outcome.error, None);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "limits_hit"), Some(json!(["payload_size"])));
+    // running out of input is the limit, not a parser error to recover from
+    assert_eq!(get_value(&nf, "parse_error"), Some(json!("")));
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    let diagnostics = get_value(&diag, "diagnostics").unwrap();
+    let codes: Vec<&str> = diagnostics.as_array().unwrap().iter().map(|d| d["code"].as_str().unwrap()).collect();
+    assert_eq!(codes, vec!["limit_exceeded"]);
+
+    assert_eq!(limits::truncate_bytes("añb", 2), "a");
+    assert_eq!(limits::truncate_bytes("añb", 3), "añ");
+    assert_eq!(limits::truncate_bytes("ab", 5), "ab");
+}
+
+#[test]
+fn test_limits_apply_to_json_feeds() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.json
+0
+{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Limited",
+  "items": [
+    {"id": "ep-1", "title": "Episode 1", "content_html": "<p>Caf\u00e9 society podcast notes that run long</p>",
+     "x_custom": true, "attachments": [{"url": "https://example.com/1.mp3", "mime_type": "audio/mpeg"}]},
+    {"id": "ep-2", "title": "Episode 2",
+     "attachments": [{"url": "https://example.com/2.mp3", "mime_type": "audio/mpeg"}]},
+    {"id": "ep-3", "title": "Episode 3",
+     "attachments": [{"url": "https://example.com/3.mp3", "mime_type": "audio/mpeg"}]}
+  ]
+}"#;
+
+    let options = FeedOptions { limits: ParseLimits { max_items: 2, ..Default::default() }, ..Default::default() };
+    let feed_id = 4306_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Partial);
+    assert_eq!(outcome.limits_hit, vec![Limit::ItemCount]);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 2);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "parse_status"), Some(json!("partial")));
+    assert_eq!(get_value(&nf, "limits_hit"), Some(json!(["item_count"])));
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([{"code": "limit_exceeded", "element": "items", "value": "item_count"}]))
+    );
+
+    // long text is cut and an item with too many keys loses the last ones (x_custom)
+    let limits = ParseLimits { max_text_length: 30, max_attributes: 4, ..Default::default() };
+    let options = FeedOptions { limits, ..Default::default() };
+    let feed_id = 4307_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Complete);
+    assert_eq!(outcome.limits_hit, vec![Limit::AttributeCount, Limit::TextLength]);
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 3);
+    assert_eq!(get_value(&items[0], "title"), Some(json!("Episode 1")));
+    assert_eq!(get_value(&items[0], "description"), Some(json!("<p>Caf\u{e9} society podcast notes")));
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([
+            {"code": "limit_exceeded", "element": "items", "value": "attribute_count"},
+            {"code": "limit_exceeded", "element": "content_html", "value": "text_length"},
+        ]))
+    );
+
+    let options = FeedOptions {
+        limits: ParseLimits { timeout: std::time::Duration::ZERO, ..Default::default() },
+        ..Default::default()
+    };
+    let feed_id = 4308_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Failed);
+    assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+}
+
+// Reads the given bytes, then panics instead of reaching the end
+struct PanickingReader {
+    data: Cursor<Vec<u8>>,
+}
+
+impl Read for PanickingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.data.read(buf)? {
+            0 => panic!("connection reset while reading"),
+            n => Ok(n),
+        }
+    }
+}
+
+#[test]
+fn test_feed_timeout_rolls_back_output() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Limited</title><description>Café society podcast</description>
+<item><title>Ep 1</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 2</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 3</title><enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    let options = FeedOptions {
+        limits: ParseLimits { timeout: std::time::Duration::ZERO, ..Default::default() },
+        ..Default::default()
+    };
+    let feed_id = 4401_i64;
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Failed);
+    assert_eq!(outcome.error.as_deref(), Some("timed out after 0ns"));
+    assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+    assert!(output_records(&out_dir, "nfitems", feed_id).is_empty());
+}
+
+#[test]
+fn test_feed_panic_is_isolated() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Limited</title><description>Café society podcast</description>
+<item><title>Ep 1</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 2</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 3</title><enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    let reader = PanickingReader { data: Cursor::new(feed.as_bytes().to_vec()) };
+    let feed_id = 4402_i64;
+    let outcome = process_feed_sync(reader, "<test>", Some(feed_id));
+    assert_eq!(outcome.status, models::FeedStatus::Failed);
+    assert_eq!(outcome.error.as_deref(), Some("panicked: connection reset while reading"));
+    assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+
+    // the next feed on the same thread writes normally
+    let feed_id = 4403_i64;
+    let outcome = process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    assert_eq!(outcome.status, models::FeedStatus::Complete);
+    assert_eq!(output_records(&out_dir, "newsfeeds", feed_id).len(), 1);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 3);
+}
+
+#[test]
+fn test_content_hashes_ignore_volatile_fields() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Hash Show</title>
+    <lastBuildDate>Mon, 06 Jan 2025 10:00:00 GMT</lastBuildDate>
+    <item>
+      <title>Ep 1</title>
+      <guid>ep-1</guid>
+      <podcast:transcript url="https://example.com/1.vtt" type="text/vtt"/>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Ep 2</title>
+      <guid>ep-2</guid>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(4501));
+    // a new build date and extra whitespace aren't content changes
+    let refetched = feed
+        .replace("Hash Show", "Hash  Show")
+        .replace("Mon, 06 Jan", "Tue, 07 Jan")
+        .replace("<title>Ep 2</title>", "<title> Ep 2\n</title>");
+    process_feed_sync(Cursor::new(refetched), "<test>", Some(4502));
+
+    let nf_a = single_record(&out_dir, "newsfeeds", 4501);
+    let nf_b = single_record(&out_dir, "newsfeeds", 4502);
+    let channel_hash = get_value(&nf_a, "content_hash").unwrap();
+    assert_eq!(channel_hash.as_str().unwrap().len(), 64);
+    assert!(channel_hash.as_str().unwrap().chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(get_value(&nf_b, "content_hash"), Some(channel_hash));
+
+    let items_a = output_records(&out_dir, "nfitems", 4501);
+    let items_b = output_records(&out_dir, "nfitems", 4502);
+    for (a, b) in items_a.iter().zip(&items_b) {
+        assert_eq!(get_value(a, "content_hash"), get_value(b, "content_hash"));
+    }
+    assert_ne!(get_value(&items_a[0], "content_hash"), get_value(&items_a[1], "content_hash"));
+}
+
+#[test]
+fn test_content_hashes_change_with_content() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Hash Show</title>
+    <item>
+      <title>Ep 1</title>
+      <guid>ep-1</guid>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Ep 2</title>
+      <guid>ep-2</guid>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(4503));
+    process_feed_sync(Cursor::new(feed.replace("Ep 2", "Ep 2 (remastered)")), "<test>", Some(4504));
+    process_feed_sync(Cursor::new(feed.replace("Hash Show", "Renamed Show")), "<test>", Some(4505));
+
+    let channel_hash = |feed_id| get_value(&single_record(&out_dir, "newsfeeds", feed_id), "content_hash");
+    let item_hashes = |feed_id| {
+        output_records(&out_dir, "nfitems", feed_id)
+            .iter()
+            .map(|r| get_value(r, "content_hash"))
+            .collect::<Vec<_>>()
+    };
+
+    // only the retitled item changes
+    assert_eq!(channel_hash(4503), channel_hash(4504));
+    let (items_a, items_b) = (item_hashes(4503), item_hashes(4504));
+    assert_eq!(items_a[0], items_b[0]);
+    assert_ne!(items_a[1], items_b[1]);
+
+    assert_ne!(channel_hash(4503), channel_hash(4505));
+    assert_eq!(items_a, item_hashes(4505));
+}
+
+#[test]
+fn test_incremental_emits_only_changes() {
+    let out_dir = ensure_output_dir();
+    let options = FeedOptions {
+        state_store: Some(state_store::StateStore::open(unique_temp_dir())),
+        incremental: true,
+        ..Default::default()
+    };
+    let feed_id = 4601_i64;
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Incremental</title>
+<item><guid>ep-1</guid><title>One</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><guid>ep-2</guid><title>Two</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+<item><guid>ep-3</guid><title>Three</title><enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 3);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "items_added"), Some(json!(3)));
+    assert_eq!(get_value(&nf, "items_changed"), Some(json!(0)));
+    assert_eq!(get_value(&nf, "items_removed"), Some(json!(0)));
+
+    let second = feed
+        .replace("<title>Two</title>", "<title>Two (edited)</title>")
+        .replace("ep-3</guid><title>Three", "ep-4</guid><title>Four");
+    process_feed_sync_with_options(Cursor::new(second.clone()), "<test>", Some(feed_id), &options);
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    let written: Vec<_> = items[3..].iter().map(|r| get_value(r, "guid").unwrap()).collect();
+    assert_eq!(written, vec![json!("ep-2"), json!("ep-4")]);
+
+    let tombstones = output_records(&out_dir, "tombstones", feed_id);
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(get_value(&tombstones[0], "guid"), Some(json!("ep-3")));
+    assert_eq!(get_value(&tombstones[0], "content_hash"), get_value(&items[2], "content_hash"));
+
+    let newsfeeds = output_records(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(newsfeeds.len(), 2);
+    assert_eq!(get_value(&newsfeeds[1], "items_added"), Some(json!(1)));
+    assert_eq!(get_value(&newsfeeds[1], "items_changed"), Some(json!(1)));
+    assert_eq!(get_value(&newsfeeds[1], "items_removed"), Some(json!(1)));
+
+    // nothing changed: only the newsfeeds record is written
+    process_feed_sync_with_options(Cursor::new(second), "<test>", Some(feed_id), &options);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 5);
+    let newsfeeds = output_records(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&newsfeeds[2], "items_added"), Some(json!(0)));
+    assert_eq!(get_value(&newsfeeds[2], "items_changed"), Some(json!(0)));
+    assert_eq!(get_value(&newsfeeds[2], "items_removed"), Some(json!(0)));
+
+    // counts are null outside incremental mode
+    let feed_id = 4602_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "items_added"), Some(JsonValue::Null));
+    assert_eq!(get_value(&nf, "items_changed"), Some(JsonValue::Null));
+    assert_eq!(get_value(&nf, "items_removed"), Some(JsonValue::Null));
+}
+
+#[test]
+fn test_incremental_partial_and_failed_runs_keep_state() {
+    let out_dir = ensure_output_dir();
+    let options = FeedOptions {
+        state_store: Some(state_store::StateStore::open(unique_temp_dir())),
+        incremental: true,
+        ..Default::default()
+    };
+    let feed_id = 4603_i64;
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Incremental</title>
+<item><guid>ep-1</guid><title>One</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><guid>ep-2</guid><title>Two</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+    process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+
+    // parsing stops inside the first item, so ep-2 must not be reported as removed
+    let partial = feed.replacen("</item>", "</itme>", 1);
+    let outcome = process_feed_sync_with_options(Cursor::new(partial), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Partial);
+    assert!(output_records(&out_dir, "tombstones", feed_id).is_empty());
+
+    // a failed run doesn't touch the stored state
+    let failing = FeedOptions {
+        limits: ParseLimits { timeout: std::time::Duration::ZERO, ..Default::default() },
+        state_store: options.state_store,
+        incremental: true,
+    };
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &failing);
+    assert_eq!(outcome.status, models::FeedStatus::Failed);
+
+    let index = failing.state_store.as_ref().unwrap().load(feed_id).unwrap();
+    assert_eq!(index.items.keys().collect::<Vec<_>>(), vec!["ep-1", "ep-2"]);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 2);
+}
+
+#[test]
+fn test_incremental_reemits_date_and_duration_changes() {
+    let out_dir = ensure_output_dir();
+    let options = FeedOptions {
+        state_store: Some(state_store::StateStore::open(unique_temp_dir())),
+        incremental: true,
+        ..Default::default()
+    };
+    let feed_id = 4604_i64;
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"><channel><title>Incremental</title>
+<item><guid>ep-1</guid><title>One</title><pubDate>Mon, 06 Jan 2025 10:00:00 GMT</pubDate><itunes:duration>30:00</itunes:duration><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+    process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+
+    // only the pub date moves
+    let redated = feed.replace("Mon, 06 Jan", "Tue, 07 Jan");
+    process_feed_sync_with_options(Cursor::new(redated.clone()), "<test>", Some(feed_id), &options);
+    // only the duration is corrected
+    let retimed = redated.replace("30:00", "31:15");
+    process_feed_sync_with_options(Cursor::new(retimed), "<test>", Some(feed_id), &options);
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    assert_eq!(items.len(), 3);
+    assert_eq!(get_value(&items[1], "pub_date"), Some(json!(1736244000)));
+    assert_eq!(get_value(&items[2], "itunes_duration"), Some(json!(1875)));
+    for newsfeeds in &output_records(&out_dir, "newsfeeds", feed_id)[1..] {
+        assert_eq!(get_value(newsfeeds, "items_changed"), Some(json!(1)));
+    }
+}
+
+#[test]
+fn test_unchanged_feeds_are_skipped() {
+    let out_dir = ensure_output_dir();
+    let options = FeedOptions {
+        state_store: Some(state_store::StateStore::open(unique_temp_dir())),
+        ..Default::default()
+    };
+    let feed_id = 4701_i64;
+    let feed = r#"1700000000
+"abc"
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Validated</title>
+<item><guid>ep-1</guid><title>One</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Complete);
+
+    // same ETag, even with a newer Last-Modified
+    let refetched = feed.replace("1700000000", "1700000500");
+    let outcome = process_feed_sync_with_options(Cursor::new(refetched), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Unchanged);
+    let marker = single_record(&out_dir, "unchanged", feed_id);
+    assert_eq!(get_value(&marker, "etag"), Some(json!("\"abc\"")));
+    assert_eq!(get_value(&marker, "last_modified"), Some(json!(1700000500)));
+    assert_eq!(output_records(&out_dir, "newsfeeds", feed_id).len(), 1);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 1);
+
+    // both validators changed
+    let changed = feed.replace("1700000000", "1700000900").replace("\"abc\"", "\"def\"");
+    let outcome = process_feed_sync_with_options(Cursor::new(changed), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Complete);
+    assert_eq!(output_records(&out_dir, "newsfeeds", feed_id).len(), 2);
+
+    // a new ETag wins over the same Last-Modified
+    let new_etag = feed.replace("1700000000", "1700000900").replace("\"abc\"", "\"ghi\"");
+    let outcome = process_feed_sync_with_options(Cursor::new(new_etag), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Complete);
+    assert_eq!(output_records(&out_dir, "newsfeeds", feed_id).len(), 3);
+
+    // no ETag, same Last-Modified
+    let no_etag = feed.replace("1700000000", "1700000900").replace("\"abc\"", "[[NO_ETAG]]");
+    let outcome = process_feed_sync_with_options(Cursor::new(no_etag), "<test>", Some(feed_id), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Unchanged);
+    assert_eq!(output_records(&out_dir, "unchanged", feed_id).len(), 2);
+}
+
+#[test]
+fn test_unchanged_requires_a_complete_parse() {
+    let out_dir = ensure_output_dir();
+    let options = FeedOptions {
+        state_store: Some(state_store::StateStore::open(unique_temp_dir())),
+        ..Default::default()
+    };
+    let feed_id = 4702_i64;
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Validated</title>
+<item><guid>ep-1</guid><title>One</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+
+    // a missing Last-Modified (0) and ETag never match
+    for _ in 0..2 {
+        let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+        assert_eq!(outcome.status, models::FeedStatus::Complete);
+    }
+
+    let broken = feed
+        .replacen("0", "1700000000", 1)
+        .replace("[[NO_ETAG]]", "\"v1\"")
+        .replace("</item>", "</itme>");
+    for _ in 0..2 {
+        let outcome = process_feed_sync_with_options(Cursor::new(broken.clone()), "<test>", Some(feed_id), &options);
+        assert_eq!(outcome.status, models::FeedStatus::Partial);
+    }
+    assert!(output_records(&out_dir, "unchanged", feed_id).is_empty());
+    assert_eq!(output_records(&out_dir, "newsfeeds", feed_id).len(), 4);
+}
+
+#[test]
+fn test_unreadable_feeds_do_not_remember_validators() {
+    let out_dir = ensure_output_dir();
+    let options = FeedOptions {
+        state_store: Some(state_store::StateStore::open(unique_temp_dir())),
+        ..Default::default()
+    };
+    let feed_id = 4703_i64;
+    let header = "1700000000\n\"v1\"\nhttps://example.com/feed.xml\n0\n";
+
+    let bodies = [
+        ("{\"version\": \"https://jsonfeed.org/version/1.1\", \"items\": [", "EOF while parsing a list at line 1 column 58"),
+        ("{\"version\": \"2\", \"title\": \"Not a feed\"}", "missing or unknown version '2'"),
+    ];
+    for (body, error) in bodies {
+        for _ in 0..2 {
+            let feed = format!("{header}{body}");
+            let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(feed_id), &options);
+            assert_eq!(outcome.status, models::FeedStatus::Failed);
+            assert_eq!(outcome.error.as_deref(), Some(error));
+        }
+    }
+    assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+
+    // well-formed XML without a channel writes nothing, so it isn't skipped next time either
+    let page = format!("{header}<html><body><p>Not found</p></body></html>");
+    for _ in 0..2 {
+        let outcome = process_feed_sync_with_options(Cursor::new(page.clone()), "<test>", Some(feed_id), &options);
+        assert_ne!(outcome.status, models::FeedStatus::Unchanged);
+    }
+    assert!(output_records(&out_dir, "unchanged", feed_id).is_empty());
+    assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+}
+
+#[test]
+fn test_duplicate_items_keep_best_copy() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Migrated Show</title>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <enclosure url="https://old-cdn.example.com/1.mp3" length="0" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 2</title>
+      <guid>ep-2</guid>
+      <pubDate>Tue, 07 Jan 2025 10:00:00 GMT</pubDate>
+      <enclosure url="https://example.com/2.mp3" length="2000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <description>Now with show notes</description>
+      <itunes:duration>30:00</itunes:duration>
+      <enclosure url="https://example.com/1.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 2 (copy)</title>
+      <enclosure url="https://example.com/2.mp3" length="2000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>episode  2</title>
+      <pubDate>Tue, 07 Jan 2025 10:00:00 GMT</pubDate>
+      <enclosure url="https://mirror.example.com/2.mp3" length="2000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 2</title>
+      <guid>ep-2-rerun</guid>
+      <pubDate>Wed, 08 Jan 2025 10:00:00 GMT</pubDate>
+      <enclosure url="https://example.com/2-rerun.mp3" length="2000" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 4801_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let items = output_records(&out_dir, "nfitems", feed_id);
+    let kept: Vec<_> = items
+        .iter()
+        .map(|r| (get_value(r, "guid").unwrap(), get_value(r, "enclosure_url").unwrap()))
+        .collect();
+    assert_eq!(
+        kept,
+        vec![
+            (json!("ep-1"), json!("https://example.com/1.mp3")),
+            (json!("ep-2"), json!("https://example.com/2.mp3")),
+            (json!("ep-2-rerun"), json!("https://example.com/2-rerun.mp3")),
+        ]
+    );
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "duplicate_items"), Some(json!(3)));
+    assert_eq!(get_value(&nf, "item_count"), Some(json!(3)));
+
+    let diag = single_record(&out_dir, "diagnostics", feed_id);
+    assert_eq!(
+        get_value(&diag, "diagnostics"),
+        Some(json!([
+            {"code": "duplicate_item", "element": "guid", "value": "ep-1"},
+            {"code": "duplicate_item", "element": "enclosure_url", "value": "https://example.com/2.mp3"},
+            {"code": "duplicate_item", "element": "title_pub_date", "value": "https://mirror.example.com/2.mp3"},
+        ]))
+    );
+}
+
+#[test]
+fn test_distinct_guids_sharing_an_enclosure_are_kept() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Trailer Show</title>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <pubDate>Mon, 06 Jan 2025 10:00:00 GMT</pubDate>
+      <enclosure url="https://example.com/trailer.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-2</guid>
+      <pubDate>Mon, 06 Jan 2025 10:00:00 GMT</pubDate>
+      <enclosure url="https://example.com/trailer.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 4805_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let guids: Vec<_> = output_records(&out_dir, "nfitems", feed_id)
+        .iter()
+        .map(|r| get_value(r, "guid").unwrap())
+        .collect();
+    assert_eq!(guids, vec![json!("ep-1"), json!("ep-2")]);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "duplicate_items"), Some(json!(0)));
+}
+
+#[test]
+fn test_no_duplicates_reported_for_distinct_items() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Distinct</title>
+<item><title>Ep 1</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 2</title><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+<item><title>Ep 3</title><enclosure url="https://example.com/3.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+    let feed_id = 4802_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 3);
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "duplicate_items"), Some(json!(0)));
+    assert!(output_records(&out_dir, "diagnostics", feed_id).is_empty());
+}
+
+#[test]
+fn test_items_outside_a_channel_are_written() {
+    let out_dir = ensure_output_dir();
+    let header = "0\n[[NO_ETAG]]\nhttps://example.com/feed.xml\n0\n";
+
+    // an item after </channel> is written straight away, as the channel is already out
+    let feed = format!(
+        r#"{header}<rss version="2.0"><channel><title>Show</title>
+<item><guid>ep-1</guid><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel>
+<item><guid>ep-2</guid><enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg"/></item>
+</rss>"#
+    );
+    let feed_id = 4803_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let guids: Vec<_> = output_records(&out_dir, "nfitems", feed_id)
+        .iter()
+        .map(|r| get_value(r, "guid").unwrap())
+        .collect();
+    assert_eq!(guids, vec![json!("ep-1"), json!("ep-2")]);
+    assert_eq!(output_records(&out_dir, "newsfeeds", feed_id).len(), 1);
+
+    // no channel at all
+    let feed = format!(
+        r#"{header}<rss version="2.0">
+<item><guid>ep-1</guid><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</rss>"#
+    );
+    let feed_id = 4804_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+    let item = single_record(&out_dir, "nfitems", feed_id);
+    assert_eq!(get_value(&item, "guid"), Some(json!("ep-1")));
+    assert!(output_records(&out_dir, "newsfeeds", feed_id).is_empty());
+}
+
+#[test]
+fn test_outcome_counts_items_and_bytes() {
+    let out_dir = ensure_output_dir();
+    // Two good items and one each without an enclosure, with a non-http enclosure, and repeated
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Skipping Show</title>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <enclosure url="https://example.com/1.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Blog post</title>
+      <guid>post-1</guid>
+    </item>
+    <item>
+      <title>Episode 2</title>
+      <guid>ep-2</guid>
+      <enclosure url="ftp://example.com/2.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 3</title>
+      <guid>ep-3</guid>
+      <enclosure url="https://example.com/3.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <enclosure url="https://example.com/1.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 4901_i64;
+    let outcome = process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    assert_eq!(outcome.items_emitted, 2);
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 2);
+    let skipped: Vec<_> = outcome.items_skipped.into_iter().collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (models::SkipReason::NoEnclosure, 1),
+            (models::SkipReason::NonHttpEnclosure, 1),
+            (models::SkipReason::Duplicate, 1),
+        ]
+    );
+    assert_eq!(outcome.bytes_read, feed.len() as u64);
+
+    // a failed feed reports its bytes but none of its items
+    let options = FeedOptions {
+        limits: ParseLimits { timeout: std::time::Duration::ZERO, ..Default::default() },
+        ..Default::default()
+    };
+    let outcome = process_feed_sync_with_options(Cursor::new(feed), "<test>", Some(4902), &options);
+    assert_eq!(outcome.status, models::FeedStatus::Failed);
+    assert_eq!(outcome.items_emitted, 0);
+    assert!(outcome.items_skipped.is_empty());
+    assert_eq!(outcome.bytes_read, feed.len() as u64);
+}
+
+#[test]
+fn test_run_summary_totals() {
+    let run = |name: &str, status: models::FeedStatus, millis: u64, emitted: usize, skipped: &[(models::SkipReason, usize)]| {
+        summary::FeedRun {
+            source_name: name.to_string(),
+            feed_id: name.split('_').next().and_then(|id| id.parse().ok()),
+            outcome: models::FeedOutcome {
+                status,
+                items_emitted: emitted,
+                items_skipped: skipped.iter().copied().collect(),
+                bytes_read: 1000,
+                ..Default::default()
+            },
+            elapsed: std::time::Duration::from_millis(millis),
+        }
+    };
+    let mut runs = vec![
+        run("1_200.txt", models::FeedStatus::Complete, 40, 10, &[(models::SkipReason::NoEnclosure, 2)]),
+        run("2_200.txt", models::FeedStatus::Partial, 250, 3, &[(models::SkipReason::Duplicate, 1)]),
+        run("3_200.txt", models::FeedStatus::Failed, 35_000, 0, &[]),
+        run("4_304.txt", models::FeedStatus::Unchanged, 2, 0, &[]),
+    ];
+    for i in 0..8 {
+        runs.push(run(&format!("{}_200.txt", 10 + i), models::FeedStatus::Complete, 10 + i, 1, &[(models::SkipReason::NoEnclosure, 1)]));
+    }
+
+    let summary = summary::build(&runs, 1700000000, std::time::Duration::from_secs(40));
+    let json = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["started_at"], json!(1700000000));
+    assert_eq!(json["runtime_ms"], json!(40000.0));
+    assert_eq!(
+        json["feeds"],
+        json!({"processed": 12, "complete": 9, "recovered": 0, "partial": 1, "failed": 1, "unchanged": 1})
+    );
+    assert_eq!(
+        json["items"],
+        json!({"emitted": 21, "skipped": 11, "skipped_by_reason": {"no_enclosure": 10, "duplicate": 1}})
+    );
+    assert_eq!(json["bytes_read"], json!(12000));
+    assert_eq!(json["feed_time_ms"], json!({"p50": 14.0, "p90": 250.0, "p99": 35000.0, "max": 35000.0}));
+
+    let slowest = json["slowest_feeds"].as_array().unwrap();
+    assert_eq!(slowest.len(), 10);
+    assert_eq!(
+        slowest[0],
+        json!({"source": "3_200.txt", "feed_id": 3, "status": "failed", "elapsed_ms": 35000.0})
+    );
+    assert_eq!(slowest[1]["source"], json!("2_200.txt"));
+    assert_eq!(slowest[9]["source"], json!("11_200.txt"));
+}
+
+#[test]
+fn test_skipped_items_are_listed_with_reasons() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Missing Episodes</title>
+    <item>
+      <title>Kept</title>
+      <guid>ep-0</guid>
+      <enclosure url="https://example.com/0.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title> Show notes only </title>
+      <guid>ep-1</guid>
+    </item>
+    <item>
+      <title>Blank URL</title>
+      <guid>ep-2</guid>
+      <enclosure url="  " length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Local file</title>
+      <guid>ep-3</guid>
+      <enclosure url="file:///Users/host/ep3.mp3" length="1000" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>No host</title>
+      <enclosure url="http://" length="1000" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+    let feed_id = 5021_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    assert_eq!(output_records(&out_dir, "nfitems", feed_id).len(), 1);
+    let skipped = single_record(&out_dir, "skipped_items", feed_id);
+    assert_eq!(
+        get_value(&skipped, "skipped_items"),
+        Some(json!([
+            {"title": "Show notes only", "guid": "ep-1", "reason": "no_enclosure"},
+            {"title": "Blank URL", "guid": "ep-2", "reason": "empty_url"},
+            {"title": "Local file", "guid": "ep-3", "reason": "non_http_enclosure"},
+            {"title": "No host", "guid": "", "reason": "invalid_url"},
+        ]))
+    );
+
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "skipped_no_enclosure"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "skipped_empty_url"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "skipped_non_http_enclosure"), Some(json!(1)));
+    assert_eq!(get_value(&nf, "skipped_invalid_url"), Some(json!(1)));
+
+    // a feed that keeps every item writes no list
+    let kept = r#"0
+[[NO_ETAG]]
+https://example.com/feed.xml
+0
+<rss version="2.0"><channel><title>Every Episode</title>
+<item><title>Ep 1</title><enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+</channel></rss>"#;
+    let feed_id = 5022_i64;
+    process_feed_sync(Cursor::new(kept), "<test>", Some(feed_id));
+    assert!(output_records(&out_dir, "skipped_items", feed_id).is_empty());
+    let nf = single_record(&out_dir, "newsfeeds", feed_id);
+    assert_eq!(get_value(&nf, "skipped_no_enclosure"), Some(json!(0)));
+}
+
+#[test]
+fn test_json_feed_skipped_attachments() {
+    let out_dir = ensure_output_dir();
+    let feed = r#"0
+[[NO_ETAG]]
+https://example.com/feed.json
+0
+{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "JSON Show",
+  "items": [
+    {"id": "post-1", "title": "A post"},
+    {"id": "ep-1", "title": "Magnet only", "attachments": [{"url": "magnet:?xt=urn:btih:abc", "mime_type": "audio/mpeg"}]}
+  ]
+}"#;
+    let feed_id = 5023_i64;
+    process_feed_sync(Cursor::new(feed), "<test>", Some(feed_id));
+
+    let skipped = single_record(&out_dir, "skipped_items", feed_id);
+    assert_eq!(
+        get_value(&skipped, "skipped_items"),
+        Some(json!([
+            {"title": "A post", "guid": "post-1", "reason": "no_enclosure"},
+            {"title": "Magnet only", "guid": "ep-1", "reason": "non_http_enclosure"},
+        ]))
+    );
+}
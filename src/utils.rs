@@ -1,7 +1,8 @@
-use chrono::DateTime;
+use url::Url;
 use xml::ParserConfig;
 
 
+
 pub fn clean_string(s: &str) -> String {
     s.trim().replace(r#"(\r\n|\n|\r)"#, "")
 }
@@ -14,52 +15,19 @@ pub fn truncate_int(number: i32) -> i32 {
     number.clamp(-2147483647, 2147483647)
 }
 
-fn contains_non_latin_codepoints(s: &str) -> bool {
-    s.chars().any(|c| c as u32 > 0x00FF)
-}
-
-fn replace_non_latin_characters(s: &str) -> String {
-    s.replace(r#"[^\x00-\x80]"#, " ")
-}
-
-pub fn sanitize_url(url: &str) -> String {
-    if url.is_empty() {
-        return String::new();
-    }
-
-    if contains_non_latin_codepoints(url) {
-        let encoded = urlencoding::encode(&url);
-        let mut new_url = truncate_string(&encoded, 768);
-
-        if contains_non_latin_codepoints(&new_url) {
-            new_url = replace_non_latin_characters(&new_url);
-        }
-
-        return truncate_string(&new_url, 768);
+// Resolve a possibly relative URL against a base (xml:base or the feed URL).
+// Absolute or unresolvable values are returned unchanged.
+pub fn resolve_url(base: &str, href: &str) -> String {
+    if href.is_empty() || Url::parse(href).is_ok() {
+        return href.to_string();
     }
 
-    truncate_string(url, 768)
+    Url::parse(base)
+        .and_then(|base| base.join(href))
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| href.to_string())
 }
 
-pub fn pub_date_to_timestamp(pub_date: &str) -> i64 {
-    let pub_date_str = pub_date.trim();
-    if pub_date_str.is_empty() {
-        return 0; // bad pub date, return 0
-    }
-
-    if let Ok(num) = pub_date_str.parse::<i64>() {
-        return num; // already a timestamp
-    }
-
-    // parse rfc 2882 (rss spec) and iso 8601 (rfc 3339)
-    DateTime::parse_from_rfc2822(pub_date_str)
-        .or_else(|_| DateTime::parse_from_rfc3339(pub_date_str))
-        .map(|dt| dt.timestamp())
-        .unwrap_or(0) // return timestamp or 0 if error
-}
-
-
-
 pub fn calculate_update_frequency(pubdates: &[i64]) -> i32 {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -107,63 +75,6 @@ pub fn calculate_update_frequency(pubdates: &[i64]) -> i32 {
     0
 }
 
-//Get a mime-type string for an unknown media enclosure
-pub fn guess_enclosure_type(url: &str) -> String {
-    if url.contains(".m4v") {
-        return "video/mp4".to_string();
-    }
-    if url.contains(".mp4") {
-        return "video/mp4".to_string();
-    }
-    if url.contains(".avi") {
-        return "video/avi".to_string();
-    }
-    if url.contains(".mov") {
-        return "video/quicktime".to_string();
-    }
-    if url.contains(".mp3") {
-        return "audio/mpeg".to_string();
-    }
-    if url.contains(".m4a") {
-        return "audio/mp4".to_string();
-    }
-    if url.contains(".wav") {
-        return "audio/wav".to_string();
-    }
-    if url.contains(".ogg") {
-        return "audio/ogg".to_string();
-    }
-    if url.contains(".wmv") {
-        return "video/x-ms-wmv".to_string();
-    }
-
-    "".to_string()
-}
-
-/*
-* Convert time string to seconds
-* 01:02 = 62 seconds
-* Thanks to Glenn Bennett!
-*/
-pub fn time_to_seconds(time_string: &str) -> i32 {
-    let parts = time_string.split(':').collect::<Vec<&str>>();
-
-    match parts.len() - 1 {
-        1 => {
-            let minutes = parts[0].parse::<i32>().unwrap_or(0);
-            let seconds = parts[1].parse::<i32>().unwrap_or(0);
-            minutes * 60 + seconds
-        }
-        2 => {
-            let hours = parts[0].parse::<i32>().unwrap_or(0);
-            let minutes = parts[1].parse::<i32>().unwrap_or(0);
-            let seconds = parts[2].parse::<i32>().unwrap_or(0);
-            hours * 3600 + minutes * 60 + seconds
-        }
-        _ => time_string.parse::<i32>().unwrap_or(30 * 60),
-    }
-}
-
 pub fn add_html_entities_to_parser_config(config: ParserConfig) -> ParserConfig {
     config
         .add_entity("amp", "&")
@@ -182,7 +93,7 @@ pub fn add_html_entities_to_parser_config(config: ParserConfig) -> ParserConfig
         .add_entity("ordf", "ª")
         .add_entity("laquo", "«")
         .add_entity("not", "¬")
-        .add_entity("shy", "­")
+        .add_entity("shy", "\u{AD}")
         .add_entity("reg", "®")
         .add_entity("macr", "¯")
         .add_entity("deg", "°")